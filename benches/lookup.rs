@@ -0,0 +1,62 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use jprops::Properties;
+
+fn bundle(size: usize) -> Properties<'static> {
+    (0..size)
+        .map(|i| (format!("message.{i}.title"), format!("Title number {i}")))
+        .collect()
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+
+    for size in [100, 1_000, 20_000] {
+        let keys = (0..size)
+            .step_by(size / 100)
+            .map(|i| format!("message.{i}.title"))
+            .collect::<Vec<_>>();
+
+        let scan = bundle(size);
+        let indexed = bundle(size).indexed();
+
+        group.bench_with_input(BenchmarkId::new("scan", size), &keys, |b, keys| {
+            b.iter(|| {
+                for key in keys {
+                    black_box(scan.get(key));
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("indexed", size), &keys, |b, keys| {
+            b.iter(|| {
+                for key in keys {
+                    black_box(indexed.get(key));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn prefix(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prefix");
+
+    for size in [100, 1_000, 20_000] {
+        let scan = bundle(size);
+        let indexed = bundle(size).indexed();
+
+        group.bench_function(BenchmarkId::new("scan", size), |b| {
+            b.iter(|| black_box(scan.prefix("message.42").len()))
+        });
+
+        group.bench_function(BenchmarkId::new("indexed", size), |b| {
+            b.iter(|| black_box(indexed.prefix("message.42").len()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, lookup, prefix);
+criterion_main!(benches);
@@ -0,0 +1,493 @@
+//!
+//! Querying and editing properties files from the command line
+//!
+//! Exits with 0 on success, 1 when a key is not found, files differ or problems
+//! are found, and 2 when the arguments or input are unusable.
+//!
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use jprops::document::PropertiesDocument;
+use jprops::layered::Layered;
+use jprops::{EnvLookup, Lookups, Properties, Separator, WriteOptions};
+
+const NOT_FOUND: u8 = 1;
+const FAILED: u8 = 2;
+
+/// Query and edit java properties files
+#[derive(Parser)]
+#[command(name = "jprops", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the value of a key, exiting with 1 if there is none
+    Get {
+        key: String,
+
+        /// The file to read, stdin if missing or `-`
+        file: Option<PathBuf>,
+
+        /// Print this when there is no value instead of failing
+        #[arg(long)]
+        default: Option<String>,
+
+        /// Expand `${key}` and `${env:VAR}` references
+        #[arg(long)]
+        resolve: bool,
+    },
+
+    /// Set the value of a key, keeping comments and formatting
+    Set {
+        key: String,
+
+        value: String,
+
+        /// The file to edit, stdin if missing or `-`
+        file: Option<PathBuf>,
+
+        /// Write the file back instead of printing it
+        #[arg(short, long)]
+        in_place: bool,
+    },
+
+    /// Delete every pair with a key, exiting with 1 if there was none
+    Delete {
+        key: String,
+
+        /// The file to edit, stdin if missing or `-`
+        file: Option<PathBuf>,
+
+        /// Write the file back instead of printing it
+        #[arg(short, long)]
+        in_place: bool,
+    },
+
+    /// Print every pair
+    List {
+        /// The file to read, stdin if missing or `-`
+        file: Option<PathBuf>,
+
+        /// Only the keys under this prefix, relative to it
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Expand `${key}` and `${env:VAR}` references
+        #[arg(long)]
+        resolve: bool,
+    },
+
+    /// Print every key
+    Keys {
+        /// The file to read, stdin if missing or `-`
+        file: Option<PathBuf>,
+
+        /// Only the keys under this prefix, relative to it
+        #[arg(long)]
+        prefix: Option<String>,
+    },
+
+    /// Print the files merged together, later files taking precedence
+    Merge {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        #[command(flatten)]
+        format: Format,
+    },
+
+    /// Rewrite a file in a consistent format, which drops comments
+    Fmt {
+        /// The file to format, stdin if missing or `-`
+        file: Option<PathBuf>,
+
+        /// Write the file back instead of printing it
+        #[arg(short, long)]
+        in_place: bool,
+
+        #[command(flatten)]
+        format: Format,
+    },
+
+    /// Report every problem in files, exiting with 1 if there are any
+    Check {
+        /// The files to check, stdin if there are none
+        files: Vec<PathBuf>,
+
+        /// Do not report keys defined more than once
+        #[arg(long)]
+        allow_duplicates: bool,
+    },
+
+    /// Show the pairs removed, added or changed between two files, exiting with 1 if there are any
+    Diff { old: PathBuf, new: PathBuf },
+}
+
+#[derive(Args)]
+struct Format {
+    /// Order the pairs by key
+    #[arg(long)]
+    sort: bool,
+
+    /// What to put between keys and values
+    #[arg(long, value_enum, default_value_t = Sep::Equals)]
+    separator: Sep,
+
+    /// Escape anything outside of ascii
+    #[arg(long)]
+    ascii: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Sep {
+    /// `key=value`
+    Equals,
+
+    /// `key:value`
+    Colon,
+
+    /// `key = value`
+    Spaced,
+}
+
+impl Format {
+    fn options(&self) -> WriteOptions {
+        WriteOptions {
+            separator: match self.separator {
+                Sep::Equals => Separator::Equals,
+                Sep::Colon => Separator::Colon,
+                Sep::Spaced => Separator::SpacedEquals,
+            },
+            ascii_only: self.ascii,
+            sorted: self.sort,
+            ..Default::default()
+        }
+    }
+}
+
+/// Content read from a file or stdin
+struct Input {
+    name: String,
+    path: Option<PathBuf>,
+    content: Vec<u8>,
+}
+
+impl Input {
+    fn read(path: Option<&Path>) -> Result<Input, String> {
+        match path {
+            Some(path) if path != Path::new("-") => {
+                let content = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+                Ok(Input {
+                    name: path.display().to_string(),
+                    path: Some(path.to_path_buf()),
+                    content,
+                })
+            }
+
+            _ => {
+                let mut content = Vec::new();
+
+                std::io::stdin()
+                    .read_to_end(&mut content)
+                    .map_err(|e| format!("stdin: {e}"))?;
+
+                Ok(Input {
+                    name: "stdin".to_string(),
+                    path: None,
+                    content,
+                })
+            }
+        }
+    }
+
+    fn load(&self) -> Result<Properties<'_>, String> {
+        Properties::load(&self.content).map_err(|e| self.error(&e))
+    }
+
+    fn document(&self) -> Result<PropertiesDocument, String> {
+        PropertiesDocument::parse(&self.content).map_err(|e| self.error(&e))
+    }
+
+    fn error(&self, e: &jprops::Error) -> String {
+        format!("{}: {}", self.name, e.render(&self.content).trim_end())
+    }
+
+    /// Writes edited content back to the file, or to stdout
+    fn write(&self, in_place: bool, content: &[u8]) -> Result<(), String> {
+        match (&self.path, in_place) {
+            (Some(path), true) => {
+                fs::write(path, content).map_err(|e| format!("{}: {e}", path.display()))
+            }
+            (None, true) => Err("--in-place needs a file".to_string()),
+            (_, false) => print(content),
+        }
+    }
+}
+
+fn print(content: &[u8]) -> Result<(), String> {
+    std::io::stdout()
+        .write_all(content)
+        .map_err(|e| format!("stdout: {e}"))
+}
+
+/// One pair formatted as it would be in a file
+fn pair(key: &str, value: &str) -> String {
+    let mut props = Properties::default();
+    props.insert_str(key, value);
+
+    props.to_string()
+}
+
+fn resolve(props: &Properties, name: &str) -> Result<Properties<'static>, String> {
+    let lookups = Lookups::new().with("env", EnvLookup);
+
+    props
+        .resolve_with(&lookups)
+        .map_err(|e| format!("{name}: {e}"))
+}
+
+fn run(command: Command) -> Result<u8, String> {
+    match command {
+        Command::Get {
+            key,
+            file,
+            default,
+            resolve: expand,
+        } => {
+            let input = Input::read(file.as_deref())?;
+            let mut props = input.load()?;
+
+            if expand {
+                props = resolve(&props, &input.name)?;
+            }
+
+            match props.get(&key).map(str::to_string).or(default) {
+                Some(value) => {
+                    print(format!("{value}\n").as_bytes())?;
+                    Ok(0)
+                }
+                None => Ok(NOT_FOUND),
+            }
+        }
+
+        Command::Set {
+            key,
+            value,
+            file,
+            in_place,
+        } => {
+            let input = Input::read(file.as_deref())?;
+            let mut doc = input.document()?;
+
+            doc.set(&key, &value);
+            input.write(in_place, doc.to_string().as_bytes())?;
+
+            Ok(0)
+        }
+
+        Command::Delete {
+            key,
+            file,
+            in_place,
+        } => {
+            let input = Input::read(file.as_deref())?;
+            let mut doc = input.document()?;
+            let found = doc.get(&key).is_some();
+
+            doc.delete(&key);
+            input.write(in_place, doc.to_string().as_bytes())?;
+
+            Ok(if found { 0 } else { NOT_FOUND })
+        }
+
+        Command::List {
+            file,
+            prefix,
+            resolve: expand,
+        } => {
+            let input = Input::read(file.as_deref())?;
+            let mut props = input.load()?;
+
+            if expand {
+                props = resolve(&props, &input.name)?;
+            }
+
+            if let Some(prefix) = prefix {
+                props = props.strip_prefix(&prefix);
+            }
+
+            print(props.to_string().as_bytes())?;
+
+            Ok(0)
+        }
+
+        Command::Keys { file, prefix } => {
+            let input = Input::read(file.as_deref())?;
+            let mut props = input.load()?;
+
+            if let Some(prefix) = prefix {
+                props = props.strip_prefix(&prefix);
+            }
+
+            let mut out = String::new();
+
+            for key in props.keys() {
+                out.push_str(key);
+                out.push('\n');
+            }
+
+            print(out.as_bytes())?;
+
+            Ok(0)
+        }
+
+        Command::Merge { files, format } => {
+            let inputs = files
+                .iter()
+                .map(|path| Input::read(Some(path)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut layered = Layered::new();
+
+            for input in &inputs {
+                layered.push(input.name.clone(), input.load()?);
+            }
+
+            let mut out = Vec::new();
+
+            layered
+                .to_properties()
+                .write_to(&mut out, &format.options())
+                .map_err(|e| e.to_string())?;
+
+            print(&out)?;
+
+            Ok(0)
+        }
+
+        Command::Fmt {
+            file,
+            in_place,
+            format,
+        } => {
+            let input = Input::read(file.as_deref())?;
+            let props = input.load()?;
+
+            let mut opts = format.options();
+            opts.line_ending = props.line_ending().unwrap_or_default();
+
+            let mut out = Vec::new();
+
+            props.write_to(&mut out, &opts).map_err(|e| e.to_string())?;
+
+            input.write(in_place, &out)?;
+
+            Ok(0)
+        }
+
+        Command::Check {
+            files,
+            allow_duplicates,
+        } => {
+            let inputs = match files.is_empty() {
+                true => vec![Input::read(None)?],
+                false => files
+                    .iter()
+                    .map(|path| Input::read(Some(path)))
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+
+            let mut problems = 0;
+
+            for input in &inputs {
+                let (props, errors) = Properties::load_lenient(&input.content);
+
+                for e in &errors {
+                    eprintln!("{}", input.error(e));
+                }
+
+                problems += errors.len();
+
+                if allow_duplicates {
+                    continue;
+                }
+
+                for duplicate in props.duplicates() {
+                    let lines = duplicate
+                        .lines
+                        .iter()
+                        .flatten()
+                        .map(|line| line.to_string())
+                        .collect::<Vec<_>>();
+
+                    eprintln!(
+                        "{}: \"{}\" is defined on lines {}",
+                        input.name,
+                        duplicate.key,
+                        lines.join(", ")
+                    );
+
+                    problems += 1;
+                }
+            }
+
+            Ok(if problems == 0 { 0 } else { NOT_FOUND })
+        }
+
+        Command::Diff { old, new } => {
+            let old = Input::read(Some(&old))?;
+            let new = Input::read(Some(&new))?;
+
+            let before = old.load()?;
+            let after = new.load()?;
+
+            let mut out = String::new();
+            let mut seen = HashSet::new();
+
+            // only the first pair of a key counts, like lookups
+            for key in before.keys().filter(|key| seen.insert(*key)) {
+                let value = before.get(key).unwrap_or_default();
+
+                match after.get(key) {
+                    Some(now) if now == value => (),
+                    Some(now) => {
+                        out.push_str(&format!("-{}", pair(key, value)));
+                        out.push_str(&format!("+{}", pair(key, now)));
+                    }
+                    None => out.push_str(&format!("-{}", pair(key, value))),
+                }
+            }
+
+            for key in after.keys().filter(|key| seen.insert(*key)) {
+                let value = after.get(key).unwrap_or_default();
+
+                out.push_str(&format!("+{}", pair(key, value)));
+            }
+
+            print(out.as_bytes())?;
+
+            Ok(if out.is_empty() { 0 } else { NOT_FOUND })
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(code) => ExitCode::from(code),
+        Err(message) => {
+            eprintln!("jprops: {message}");
+            ExitCode::from(FAILED)
+        }
+    }
+}
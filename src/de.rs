@@ -0,0 +1,468 @@
+//!
+//! Deserializing types from properties
+//!
+//! Keys are split on `.` into nested structs and maps, `[n]` or `.n` suffixes
+//! (as well as repeating a key) make sequences, and numbers and booleans are
+//! parsed from their values.
+//!
+
+use std::collections::HashMap;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Visitor};
+use serde::Deserialize;
+
+use crate::{Error, Properties};
+
+/// Deserializes a type from properties content
+pub fn from_slice<T: DeserializeOwned>(content: &[u8]) -> Result<T, Error> {
+    let props = Properties::load(content)?;
+
+    from_properties(&props)
+}
+
+/// Deserializes a type from properties
+pub fn from_properties<'de, T: Deserialize<'de>>(props: &'de Properties<'_>) -> Result<T, Error> {
+    let mut root = Node::default();
+
+    for ((k, v), line) in props.pairs.iter().zip(&props.lines) {
+        let mut node = &mut root;
+
+        for segment in segments(k) {
+            node = node.child(segment);
+        }
+
+        node.values.push(Value {
+            key: k,
+            value: v,
+            line: *line,
+        });
+    }
+
+    T::deserialize(NodeDeserializer(&root))
+}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Serde {
+            key: None,
+            line: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl Error {
+    /// Adds where the error happened, unless it already knows
+    fn at(self, node: &Node) -> Self {
+        match self {
+            Error::Serde {
+                key: None,
+                line: None,
+                message,
+            } if !node.path.is_empty() => node.error(message),
+
+            e => e,
+        }
+    }
+}
+
+/// Splits `a.b[0][1]` into `a`, `b`, `0` and `1`
+fn segments(key: &str) -> impl Iterator<Item = &str> {
+    key.split('.').flat_map(|part| {
+        let mut segments = Vec::new();
+
+        match part.find('[') {
+            Some(open) if part.ends_with(']') => {
+                if open > 0 {
+                    segments.push(&part[..open]);
+                }
+
+                segments.extend(part[open + 1..part.len() - 1].split("]["));
+            }
+
+            _ => segments.push(part),
+        }
+
+        segments
+    })
+}
+
+#[derive(Clone, Copy)]
+struct Value<'de> {
+    key: &'de str,
+    value: &'de str,
+    line: Option<usize>,
+}
+
+/// A key segment with the values directly under it and further segments
+#[derive(Default)]
+struct Node<'de> {
+    path: String,
+    values: Vec<Value<'de>>,
+    children: Vec<(&'de str, Node<'de>)>,
+    positions: HashMap<&'de str, usize>,
+}
+
+impl<'de> Node<'de> {
+    fn child(&mut self, segment: &'de str) -> &mut Node<'de> {
+        let pos = match self.positions.get(segment) {
+            Some(pos) => *pos,
+            None => {
+                let path = if self.path.is_empty() {
+                    segment.to_string()
+                } else {
+                    format!("{}.{}", self.path, segment)
+                };
+
+                self.positions.insert(segment, self.children.len());
+                self.children.push((
+                    segment,
+                    Node {
+                        path,
+                        ..Default::default()
+                    },
+                ));
+
+                self.children.len() - 1
+            }
+        };
+
+        &mut self.children[pos].1
+    }
+
+    fn leaf(path: &str, value: Value<'de>) -> Self {
+        Node {
+            path: path.to_string(),
+            values: vec![value],
+            ..Default::default()
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.children.is_empty()
+    }
+
+    fn value(&self) -> Result<&'de str, Error> {
+        match self.values.first() {
+            Some(value) => Ok(value.value),
+            None => Err(self.error("expected a value".to_string())),
+        }
+    }
+
+    fn error(&self, message: String) -> Error {
+        let (key, line) = match self.values.first() {
+            Some(value) => (value.key.to_string(), value.line),
+            None => (self.path.clone(), None),
+        };
+
+        Error::Serde {
+            key: Some(key),
+            line,
+            message,
+        }
+    }
+}
+
+struct NodeDeserializer<'a, 'de>(&'a Node<'de>);
+
+macro_rules! parse {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let value = self.0.value()?;
+
+                match value.trim().parse() {
+                    Ok(v) => visitor.$visit(v),
+                    Err(e) => Err(self.0.error(format!("cannot parse \"{value}\": {e}"))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'a, 'de> de::Deserializer<'de> for NodeDeserializer<'a, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let node = self.0;
+
+        if !node.children.is_empty() {
+            self.deserialize_map(visitor)
+        } else if node.values.len() > 1 {
+            self.deserialize_seq(visitor)
+        } else if let Some(value) = node.values.first() {
+            visitor.visit_borrowed_str(value.value)
+        } else {
+            visitor.visit_unit()
+        }
+    }
+
+    parse! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.0.value()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_bytes(self.0.value()?.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    /// Missing keys and empty values are both none
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let node = self.0;
+
+        if node.is_empty() || (node.children.is_empty() && node.value()?.is_empty()) {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Indexed children if there are any, otherwise every value of the key
+    ///
+    /// A single empty value is an empty sequence, which is how one is written.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let node = self.0;
+
+        let seq = if node.children.is_empty()
+            && matches!(node.values.as_slice(), [v] if v.value.is_empty())
+        {
+            SeqAccess::Values(Vec::new().into_iter())
+        } else if node.children.is_empty() {
+            let leaves = node
+                .values
+                .iter()
+                .map(|value| Node::leaf(&node.path, *value))
+                .collect::<Vec<_>>();
+
+            SeqAccess::Values(leaves.into_iter())
+        } else {
+            let mut indexed = Vec::with_capacity(node.children.len());
+
+            for (segment, child) in &node.children {
+                match segment.parse::<usize>() {
+                    Ok(i) => indexed.push((i, child)),
+                    Err(_) => return Err(child.error("expected an indexed key".to_string())),
+                }
+            }
+
+            indexed.sort_by_key(|(i, _)| *i);
+
+            SeqAccess::Children(indexed.into_iter())
+        };
+
+        visitor.visit_seq(seq)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let node = self.0;
+
+        if node.children.is_empty() && !node.values.is_empty() {
+            return Err(node.error("expected nested keys".to_string()));
+        }
+
+        visitor.visit_map(MapAccess {
+            children: node.children.iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    /// Unit variants come from the value, others from a single nested key naming the variant
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let node = self.0;
+
+        match node.children.as_slice() {
+            [] => visitor.visit_enum(BorrowedStrDeserializer::new(node.value()?.trim())),
+            [(variant, child)] => visitor.visit_enum(EnumAccess { variant, child }),
+            _ => Err(node.error("expected a single variant".to_string())),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+enum SeqAccess<'a, 'de> {
+    Values(std::vec::IntoIter<Node<'de>>),
+    Children(std::vec::IntoIter<(usize, &'a Node<'de>)>),
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for SeqAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self {
+            SeqAccess::Values(values) => match values.next() {
+                Some(node) => seed
+                    .deserialize(NodeDeserializer(&node))
+                    .map(Some)
+                    .map_err(|e| e.at(&node)),
+                None => Ok(None),
+            },
+
+            SeqAccess::Children(children) => match children.next() {
+                Some((_, node)) => seed
+                    .deserialize(NodeDeserializer(node))
+                    .map(Some)
+                    .map_err(|e| e.at(node)),
+                None => Ok(None),
+            },
+        }
+    }
+}
+
+struct MapAccess<'a, 'de> {
+    children: std::slice::Iter<'a, (&'de str, Node<'de>)>,
+    value: Option<&'a Node<'de>>,
+}
+
+impl<'a, 'de> de::MapAccess<'de> for MapAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((segment, child)) = self.children.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(child);
+
+        seed.deserialize(BorrowedStrDeserializer::new(segment))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let node = self.value.take().expect("value requested before key");
+
+        seed.deserialize(NodeDeserializer(node))
+            .map_err(|e| e.at(node))
+    }
+}
+
+struct EnumAccess<'a, 'de> {
+    variant: &'de str,
+    child: &'a Node<'de>,
+}
+
+impl<'a, 'de> de::EnumAccess<'de> for EnumAccess<'a, 'de> {
+    type Error = Error;
+    type Variant = NodeDeserializer<'a, 'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?;
+
+        Ok((variant, NodeDeserializer(self.child)))
+    }
+}
+
+impl<'a, 'de> de::VariantAccess<'de> for NodeDeserializer<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,128 @@
+//!
+//! Locating errors in the content they were found in
+//!
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::Error;
+
+/// Where in the content an error was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start in the content
+    pub offset: usize,
+
+    /// Length in bytes
+    pub len: usize,
+
+    /// The line it starts on, starting at 1
+    pub line: usize,
+
+    /// The character on the line it starts at, starting at 1
+    pub column: usize,
+}
+
+impl Span {
+    /// The bytes of the content it covers
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+}
+
+impl Error {
+    /// Where the error is in the content, if it is about the content
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Error::MalformedLine(span, _)
+            | Error::InvalidUtf8(span, _)
+            | Error::InvalidEscape(span, _)
+            | Error::UnpairedSurrogate(span, _)
+            | Error::DuplicateKey(span, ..) => Some(span),
+            _ => None,
+        }
+    }
+
+    /// Renders the error along with the line of `content` it is on, pointing at the exact characters
+    ///
+    /// ```text
+    /// error: line 2 has invalid escape sequence "\q"
+    ///  --> 2:7
+    ///   |
+    /// 2 | name=a\qb
+    ///   |       ^^
+    /// ```
+    ///
+    /// `content` has to be what the error came from, errors without a span only render their message.
+    pub fn render(&self, content: &[u8]) -> String {
+        let mut out = format!("error: {self}\n");
+
+        let Some(span) = self.span() else {
+            return out;
+        };
+
+        let offset = span.offset.min(content.len());
+        let start = memchr::memrchr2(b'\n', b'\r', &content[..offset]).map_or(0, |br| br + 1);
+        let end = memchr::memchr2(b'\n', b'\r', &content[offset..])
+            .map_or(content.len(), |br| offset + br);
+
+        let line = String::from_utf8_lossy(&content[start..end]);
+        let before = String::from_utf8_lossy(&content[start..offset]);
+        let marked = String::from_utf8_lossy(&content[offset..span.range().end.clamp(offset, end)]);
+
+        let gutter = span.line.to_string();
+        let pad = " ".repeat(gutter.len());
+
+        // keep tabs so the carets line up however wide they are shown
+        let indent = before
+            .chars()
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let _ = writeln!(out, "{pad}--> {}:{}", span.line, span.column);
+        let _ = writeln!(out, "{pad} |");
+        let _ = writeln!(out, "{gutter} | {line}");
+        let _ = writeln!(
+            out,
+            "{pad} | {indent}{}",
+            "^".repeat(marked.chars().count().max(1))
+        );
+
+        out
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let code = match self {
+            Error::MalformedLine(..) => "jprops::malformed_line",
+            Error::InvalidUtf8(..) => "jprops::invalid_utf8",
+            Error::InvalidEscape(..) => "jprops::invalid_escape",
+            Error::UnpairedSurrogate(..) => "jprops::unpaired_surrogate",
+            Error::DuplicateKey(..) => "jprops::duplicate_key",
+            Error::Io(_) => "jprops::io",
+            #[cfg(feature = "serde")]
+            Error::Serde { .. } => "jprops::serde",
+        };
+
+        Some(Box::new(code))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let span = self.span()?;
+        let label = match self {
+            Error::MalformedLine(..) => "malformed",
+            Error::InvalidUtf8(..) => "not utf-8",
+            Error::InvalidEscape(..) => "invalid escape",
+            Error::DuplicateKey(..) => "defined again",
+            _ => "unpaired surrogate",
+        };
+
+        Some(Box::new(std::iter::once(miette::LabeledSpan::new(
+            Some(label.to_string()),
+            span.offset,
+            span.len,
+        ))))
+    }
+}
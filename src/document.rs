@@ -0,0 +1,311 @@
+//!
+//! Lossless representation of a properties file
+//!
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::{dump, load, Error, LineEnding, Properties, Span};
+
+/// A properties file that keeps its comments, blank lines and formatting
+///
+/// Lines that are not edited are written back byte for byte.
+#[derive(Debug, Default, Clone)]
+pub struct PropertiesDocument {
+    items: Vec<Item>,
+    line_ending: LineEnding,
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Trivia(String),
+    Entry(Entry),
+}
+
+/// A key value pair along with the text it was read from
+#[derive(Debug, Clone)]
+pub struct Entry {
+    raw: String,
+    key: String,
+    value: String,
+    line: Option<usize>,
+    span: Range<usize>,
+
+    /// Whether there is a separator between the key and the value, `flag` alone has none
+    separated: bool,
+}
+
+impl PropertiesDocument {
+    /// Attempts parsing properties content
+    pub fn parse(content: &[u8]) -> Result<Self, Error> {
+        let mut pairs = load::load(content, &Default::default())?.into_iter();
+        let mut items = Vec::new();
+        let mut rest = content;
+        let mut line = 0;
+        let mut line_ending = None;
+
+        while !rest.is_empty() {
+            let start = rest;
+            let offset = content.len() - rest.len();
+            let (cur, full) = load::next_line(&mut rest);
+            line += 1;
+
+            if line_ending.is_none() {
+                line_ending = LineEnding::of(full);
+            }
+
+            let body = load::trim_start(cur);
+
+            if body.is_empty() || load::is_comment(body) {
+                items.push(Item::Trivia(utf8(full, offset, line)?));
+                continue;
+            }
+
+            let first = line;
+
+            // the logical line along with where each of its pieces is in it and starts in the raw text
+            let mut logical = Vec::new();
+            let mut pieces = Vec::new();
+            let mut last = cur;
+            let mut last_offset = 0;
+            let mut len = full.len();
+
+            loop {
+                let body = load::trim_start(last);
+                let continued = load::odd_backslash(body);
+                let body = if continued {
+                    &body[..body.len() - 1]
+                } else {
+                    body
+                };
+
+                let raw = last_offset + last.len() - load::trim_start(last).len();
+                let from = logical.len();
+
+                logical.extend_from_slice(body);
+                pieces.push((from..logical.len(), raw));
+
+                if !continued || rest.is_empty() {
+                    break;
+                }
+
+                let (cur, full) = load::next_line(&mut rest);
+                line += 1;
+
+                last = cur;
+                last_offset = len;
+                len += full.len();
+            }
+
+            let (key_end, value_start) = load::split(&logical);
+            let separated = key_end < value_start;
+            let value_start = pieces
+                .iter()
+                .find(|(piece, _)| piece.start <= value_start && value_start <= piece.end)
+                .map_or(0, |(piece, raw)| raw + value_start - piece.start);
+
+            let value_end = value_start.max(last_offset + load::trim_end(last).len());
+
+            let Some((key, value)) = pairs.next() else {
+                let span = Span {
+                    offset,
+                    len: cur.len(),
+                    line: first,
+                    column: 1,
+                };
+
+                return Err(Error::MalformedLine(span, utf8(cur, offset, first)?));
+            };
+
+            items.push(Item::Entry(Entry {
+                raw: utf8(&start[..len], offset, first)?,
+                key: key.into_owned(),
+                value: value.into_owned(),
+                line: Some(first),
+                span: value_start..value_end,
+                separated,
+            }));
+        }
+
+        Ok(PropertiesDocument {
+            items,
+            line_ending: line_ending.unwrap_or_default(),
+        })
+    }
+
+    /// returns how many entries
+    pub fn len(&self) -> usize {
+        self.entries().count()
+    }
+
+    /// Checks if there are entries
+    pub fn is_empty(&self) -> bool {
+        self.entries().next().is_none()
+    }
+
+    /// Get the value for the first entry that matches the key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries().find(|e| e.key == key).map(Entry::value)
+    }
+
+    /// Sets the value of the first entry matching the key, appending a new entry if there is none
+    pub fn set(&mut self, key: &str, value: &str) {
+        if let Some(entry) = self.entries_mut().find(|e| e.key == key) {
+            entry.set_value(value);
+            return;
+        }
+
+        self.push(key, value);
+    }
+
+    /// Appends a new entry to the end of the document, ending lines like the rest of it
+    pub fn push(&mut self, key: &str, value: &str) {
+        let eol = self.line_ending.as_str();
+
+        if let Some(last) = self.items.last_mut() {
+            let raw = match last {
+                Item::Trivia(raw) => raw,
+                Item::Entry(entry) => {
+                    entry.end_continuation();
+                    &mut entry.raw
+                }
+            };
+
+            if !raw.ends_with(['\n', '\r']) {
+                raw.push_str(eol);
+            }
+        }
+
+        let mut raw = dump::escaped(key, true);
+        raw.push('=');
+
+        let start = raw.len();
+        raw.push_str(&dump::escaped(value, false));
+        let end = raw.len();
+
+        raw.push_str(eol);
+
+        self.items.push(Item::Entry(Entry {
+            raw,
+            key: key.to_string(),
+            value: value.to_string(),
+            line: None,
+            span: start..end,
+            separated: true,
+        }));
+    }
+
+    /// Deletes all the entries that match the key
+    pub fn delete(&mut self, key: &str) {
+        self.items
+            .retain(|item| !matches!(item, Item::Entry(entry) if entry.key == key));
+    }
+
+    /// Iterate over entries
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Entry(entry) => Some(entry),
+            Item::Trivia(_) => None,
+        })
+    }
+
+    /// Iterate over entries mutably
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = &mut Entry> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Entry(entry) => Some(entry),
+            Item::Trivia(_) => None,
+        })
+    }
+
+    /// Borrows the key value pairs as properties
+    pub fn to_properties(&self) -> Properties<'_> {
+        let mut props = Properties::default();
+
+        for e in self.entries() {
+            props.push(
+                Cow::Borrowed(e.key.as_str()),
+                Cow::Borrowed(e.value.as_str()),
+                e.line,
+            );
+        }
+
+        props
+    }
+}
+
+impl Entry {
+    /// The unescaped key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The unescaped value
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The line this entry starts on, if it was parsed rather than added
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Drops a continuation left dangling at the end of the file, so nothing after it joins the entry
+    fn end_continuation(&mut self) {
+        let body = self.raw.trim_end_matches(['\n', '\r']);
+
+        if load::odd_backslash(body.as_bytes()) {
+            self.raw.truncate(body.len() - 1);
+            self.span.start = self.span.start.min(self.raw.len());
+            self.span.end = self.span.end.min(self.raw.len());
+        }
+    }
+
+    /// Replaces the value, keeping the key, separator and anything trailing as is
+    ///
+    /// A key without a separator gets a `=` so the value does not run into it.
+    pub fn set_value(&mut self, value: &str) {
+        let escaped = dump::escaped(value, false);
+
+        if !self.separated && !escaped.is_empty() {
+            self.raw.insert(self.span.start, '=');
+            self.span = self.span.start + 1..self.span.end + 1;
+            self.separated = true;
+        }
+
+        self.raw.replace_range(self.span.clone(), &escaped);
+        self.span.end = self.span.start + escaped.len();
+        self.value = value.to_string();
+    }
+}
+
+impl std::fmt::Display for PropertiesDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for item in &self.items {
+            match item {
+                Item::Trivia(raw) => f.write_str(raw)?,
+                Item::Entry(entry) => f.write_str(&entry.raw)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the text starting at `offset` on `line`
+fn utf8(bytes: &[u8], offset: usize, line: usize) -> Result<String, Error> {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Ok(s.to_string()),
+        Err(e) => {
+            let valid = &bytes[..e.valid_up_to()];
+
+            let span = Span {
+                offset: offset + valid.len(),
+                len: e.error_len().unwrap_or(bytes.len() - valid.len()),
+                line,
+                column: String::from_utf8_lossy(valid).chars().count() + 1,
+            };
+
+            Err(Error::InvalidUtf8(span, e))
+        }
+    }
+}
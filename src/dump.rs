@@ -1,11 +1,279 @@
 use super::Properties;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Options for writing properties
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// What to put between keys and values
+    pub separator: Separator,
+
+    /// What to end lines with
+    pub line_ending: LineEnding,
+
+    /// Escapes anything outside of ascii with `\uXXXX` instead of writing utf-8
+    pub ascii_only: bool,
+
+    /// Escapes anything above `U+00FF` with `\uXXXX`, the rest is still written as utf-8
+    ///
+    /// Loading the output as [`Encoding::Latin1`](crate::Encoding::Latin1) only reads it back
+    /// correctly when everything left unescaped is ascii.
+    pub latin1_only: bool,
+
+    /// A comment to start the output with
+    pub header: Option<String>,
+
+    /// Adds a comment with the current date, like java's `Properties.store`
+    pub timestamp: bool,
+
+    /// Writes the pairs ordered by key rather than in insertion order
+    pub sorted: bool,
+}
+
+/// The separator between a key and value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Separator {
+    /// `key=value`
+    #[default]
+    Equals,
+
+    /// `key:value`
+    Colon,
+
+    /// `key = value`
+    SpacedEquals,
+}
+
+/// The line ending to write
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+
+    /// `\r\n`
+    Crlf,
+
+    /// `\r`
+    Cr,
+}
+
+impl Separator {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Separator::Equals => "=",
+            Separator::Colon => ":",
+            Separator::SpacedEquals => " = ",
+        }
+    }
+}
+
+impl LineEnding {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
+impl WriteOptions {
+    /// The highest character written as is rather than escaped
+    fn max_char(&self) -> char {
+        if self.ascii_only {
+            '\u{7f}'
+        } else if self.latin1_only {
+            '\u{ff}'
+        } else {
+            char::MAX
+        }
+    }
+}
+
+impl LineEnding {
+    /// Detects the line ending a line was terminated with
+    pub(crate) fn of(line: &[u8]) -> Option<LineEnding> {
+        match line {
+            [.., b'\r', b'\n'] => Some(LineEnding::Crlf),
+            [.., b'\n'] => Some(LineEnding::Lf),
+            [.., b'\r'] => Some(LineEnding::Cr),
+            _ => None,
+        }
+    }
+}
+
+/// Writes with the default options, but keeps the line ending the properties were read with
+/// and escapes anything above `U+00FF`, writing the rest as utf-8
 impl std::fmt::Display for Properties<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (k, v) in &self.pairs {
-            writeln!(f, "{}={}", k, v)?;
+        let opts = WriteOptions {
+            line_ending: self.line_ending.unwrap_or_default(),
+            latin1_only: true,
+            ..Default::default()
+        };
+
+        write(self, f, &opts)
+    }
+}
+
+/// Writes properties out according to the options
+pub(crate) fn write<W: Write>(
+    props: &Properties,
+    out: &mut W,
+    opts: &WriteOptions,
+) -> std::fmt::Result {
+    let eol = opts.line_ending.as_str();
+    let max = opts.max_char();
+
+    if let Some(header) = &opts.header {
+        for line in lines(header) {
+            if !line.starts_with(['#', '!']) {
+                out.write_char('#')?;
+            }
+
+            for ch in line.chars() {
+                if ch > max {
+                    escape_unicode(out, ch)?;
+                } else {
+                    out.write_char(ch)?;
+                }
+            }
+
+            out.write_str(eol)?;
         }
+    }
+
+    if opts.timestamp {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        write!(out, "#{}{}", date(now), eol)?;
+    }
+
+    let mut pairs = props.pairs.iter().collect::<Vec<_>>();
 
-        Ok(())
+    if opts.sorted {
+        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
     }
+
+    for (k, v) in pairs {
+        escape(out, k, true, max)?;
+        out.write_str(opts.separator.as_str())?;
+        escape(out, v, false, max)?;
+        out.write_str(eol)?;
+    }
+
+    Ok(())
+}
+
+/// Splits on `\r\n`, `\n` and `\r`, like java's `Properties.store` does with comments
+fn lines(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let end = rest.find(['\r', '\n']).unwrap_or(rest.len());
+        let line = &rest[..end];
+
+        rest = &rest[end..];
+        rest = rest
+            .strip_prefix("\r\n")
+            .or_else(|| rest.strip_prefix(['\r', '\n']))
+            .unwrap_or(rest);
+
+        Some(line)
+    })
+}
+
+/// Writes a key or value so that loading it gives back the same string
+///
+/// Spaces are escaped everywhere in keys but only on the edges of values,
+/// since that is the only place loading would trim them. Characters above
+/// `max` are written as `\uXXXX`.
+pub(crate) fn escape<W: Write>(out: &mut W, s: &str, key: bool, max: char) -> std::fmt::Result {
+    let last = s.len().saturating_sub(1);
+
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '\\' => out.write_str("\\\\")?,
+            '\t' => out.write_str("\\t")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\x0c' => out.write_str("\\f")?,
+            '=' | ':' | '#' | '!' => {
+                out.write_char('\\')?;
+                out.write_char(ch)?;
+            }
+            ' ' if key || i == 0 || i == last => out.write_str("\\ ")?,
+            '\0'..='\x1f' | '\x7f' => escape_unicode(out, ch)?,
+            _ if ch > max => escape_unicode(out, ch)?,
+            _ => out.write_char(ch)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes `\uXXXX`, as a surrogate pair outside of the basic multilingual plane
+fn escape_unicode<W: Write>(out: &mut W, ch: char) -> std::fmt::Result {
+    let mut buf = [0; 2];
+
+    for unit in ch.encode_utf16(&mut buf) {
+        write!(out, "\\u{:04X}", unit)?;
+    }
+
+    Ok(())
+}
+
+/// Escapes into a new string, leaving only latin-1 as is
+pub(crate) fn escaped(s: &str, key: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    escape(&mut out, s, key, '\u{ff}').expect("writing to a string does not fail");
+    out
+}
+
+/// Formats seconds since the epoch the way java's `Date.toString` does (in UTC)
+fn date(secs: u64) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let days = secs / 86400;
+    let time = secs % 86400;
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{} {} {:02} {:02}:{:02}:{:02} UTC {}",
+        DAYS[(days % 7) as usize],
+        MONTHS[(month - 1) as usize],
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+        year
+    )
+}
+
+#[test]
+fn test_date() {
+    assert_eq!(date(0), "Thu Jan 01 00:00:00 UTC 1970");
+    assert_eq!(date(951782400), "Tue Feb 29 00:00:00 UTC 2000");
+    assert_eq!(date(1792327522), "Sun Oct 18 12:45:22 UTC 2026");
 }
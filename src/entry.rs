@@ -0,0 +1,131 @@
+//!
+//! Editing a pair in place, like [`std::collections::hash_map::Entry`]
+//!
+
+use std::borrow::Cow;
+
+use crate::Properties;
+
+/// The first pair with a key, made with [`Properties::entry`]
+pub enum Entry<'a, 'bytes> {
+    /// There is a pair with the key
+    Occupied(OccupiedEntry<'a, 'bytes>),
+
+    /// There is no pair with the key
+    Vacant(VacantEntry<'a, 'bytes>),
+}
+
+/// The first pair with a key
+pub struct OccupiedEntry<'a, 'bytes> {
+    props: &'a mut Properties<'bytes>,
+    pos: usize,
+}
+
+/// A key without any pairs
+pub struct VacantEntry<'a, 'bytes> {
+    props: &'a mut Properties<'bytes>,
+    key: Cow<'bytes, str>,
+}
+
+impl<'a, 'bytes> Entry<'a, 'bytes> {
+    pub(crate) fn new(props: &'a mut Properties<'bytes>, key: Cow<'bytes, str>) -> Self {
+        match props.position(&key) {
+            Some(pos) => Entry::Occupied(OccupiedEntry { props, pos }),
+            None => Entry::Vacant(VacantEntry { props, key }),
+        }
+    }
+
+    /// The key of the entry
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// The value, appending a pair with `default` if there is none
+    pub fn or_insert(self, default: impl Into<Cow<'bytes, str>>) -> &'a mut Cow<'bytes, str> {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// The value, appending a pair with what `default` gives if there is none
+    pub fn or_insert_with<V: Into<Cow<'bytes, str>>>(
+        self,
+        default: impl FnOnce() -> V,
+    ) -> &'a mut Cow<'bytes, str> {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Edits the value if there is one
+    pub fn and_modify(mut self, f: impl FnOnce(&mut Cow<'bytes, str>)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+
+        self
+    }
+}
+
+impl<'a, 'bytes> OccupiedEntry<'a, 'bytes> {
+    /// The key of the pair
+    pub fn key(&self) -> &str {
+        &self.props.pairs[self.pos].0
+    }
+
+    /// The value of the pair
+    pub fn get(&self) -> &str {
+        &self.props.pairs[self.pos].1
+    }
+
+    /// The value of the pair, to edit it
+    pub fn get_mut(&mut self) -> &mut Cow<'bytes, str> {
+        &mut self.props.pairs[self.pos].1
+    }
+
+    /// The value of the pair, borrowed for as long as the entry was
+    pub fn into_mut(self) -> &'a mut Cow<'bytes, str> {
+        &mut self.props.pairs[self.pos].1
+    }
+
+    /// The line the pair was read from, if it was read from anywhere
+    pub fn line(&self) -> Option<usize> {
+        self.props.lines[self.pos]
+    }
+
+    /// Sets the value of the pair, giving back the old one
+    pub fn insert(&mut self, value: impl Into<Cow<'bytes, str>>) -> Cow<'bytes, str> {
+        std::mem::replace(self.get_mut(), value.into())
+    }
+
+    /// Removes just this pair, giving back its value
+    pub fn remove(self) -> Cow<'bytes, str> {
+        self.props.lines.remove(self.pos);
+        let (_, value) = self.props.pairs.remove(self.pos);
+
+        self.props.invalidate();
+
+        value
+    }
+}
+
+impl<'a, 'bytes> VacantEntry<'a, 'bytes> {
+    /// The key that has no pairs
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Appends a pair with the key, giving back its value
+    pub fn insert(self, value: impl Into<Cow<'bytes, str>>) -> &'a mut Cow<'bytes, str> {
+        self.props.push(self.key, value.into(), None);
+
+        let (_, value) = self.props.pairs.last_mut().expect("a pair was just pushed");
+
+        value
+    }
+}
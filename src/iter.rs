@@ -111,6 +111,9 @@ impl<'a, 'bytes> IntoIterator for &'a mut Properties<'bytes> {
     type IntoIter = IterMut<'a, 'bytes>;
 
     fn into_iter(self) -> Self::IntoIter {
+        // keys could be changed through the references
+        self.invalidate();
+
         IterMut(self.pairs.iter_mut())
     }
 }
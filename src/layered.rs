@@ -0,0 +1,115 @@
+//!
+//! Stacking properties on top of each other
+//!
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::Properties;
+
+/// Properties from several sources, where later layers take precedence over earlier ones
+///
+/// A typical stack is defaults, then a file, then a profile, then overrides.
+#[derive(Debug, Default)]
+pub struct Layered<'bytes> {
+    layers: Vec<(String, Properties<'bytes>)>,
+}
+
+/// A value along with where it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Found<'a> {
+    /// The value
+    pub value: &'a str,
+
+    /// The name of the layer it is in
+    pub layer: &'a str,
+
+    /// The line it was read from, if it was read from anywhere
+    pub line: Option<usize>,
+}
+
+impl<'bytes> Layered<'bytes> {
+    /// No layers at all
+    pub fn new() -> Self {
+        Layered::default()
+    }
+
+    /// Adds a layer that takes precedence over every layer already added
+    pub fn push(&mut self, name: impl Into<String>, props: Properties<'bytes>) {
+        self.layers.push((name.into(), props));
+    }
+
+    /// Adds a layer like [`Layered::push`]
+    pub fn with(mut self, name: impl Into<String>, props: Properties<'bytes>) -> Self {
+        self.push(name, props);
+        self
+    }
+
+    /// The names of the layers from lowest to highest precedence
+    pub fn layers(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The properties of a layer by name
+    pub fn layer(&self, name: &str) -> Option<&Properties<'bytes>> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, props)| props)
+    }
+
+    /// The value that wins for a key, the first one in the highest layer that has it
+    pub fn get(&self, key: &str) -> Option<Found<'_>> {
+        self.layers.iter().rev().find_map(|(name, props)| {
+            let pos = props.position(key)?;
+
+            Some(found(name, props, pos))
+        })
+    }
+
+    /// Every value for a key hidden by the one [`Layered::get`] gives, from highest precedence to lowest
+    pub fn shadowed(&self, key: &str) -> Vec<Found<'_>> {
+        let mut all = self.layers.iter().rev().flat_map(|(name, props)| {
+            props
+                .positions(key)
+                .into_iter()
+                .map(move |pos| found(name, props, pos))
+        });
+
+        all.next();
+        all.collect()
+    }
+
+    /// Every key in any layer, in the order they first appear from the lowest layer up
+    pub fn keys(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+
+        self.layers
+            .iter()
+            .flat_map(|(_, props)| props.keys())
+            .filter(|key| seen.insert(*key))
+            .collect()
+    }
+
+    /// Flattens the layers into one pair for each key with the value that wins
+    pub fn to_properties(&self) -> Properties<'_> {
+        let mut props = Properties::default();
+
+        for key in self.keys() {
+            if let Some(found) = self.get(key) {
+                props.push(Cow::Borrowed(key), Cow::Borrowed(found.value), found.line);
+            }
+        }
+
+        props
+    }
+}
+
+fn found<'a>(name: &'a str, props: &'a Properties, pos: usize) -> Found<'a> {
+    Found {
+        value: &props.pairs[pos].1,
+        layer: name,
+        line: props.lines[pos],
+    }
+}
@@ -6,40 +6,174 @@
 #![deny(missing_docs)]
 
 use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
+#[cfg(feature = "serde")]
+pub mod de;
+mod diagnostic;
+pub mod document;
 mod dump;
+pub mod entry;
 pub mod iter;
+pub mod layered;
 mod load;
+pub mod prefix;
+pub mod reader;
+mod resolve;
+#[cfg(feature = "serde")]
+pub mod ser;
+mod typed;
+
+#[cfg(feature = "serde")]
+pub use de::{from_properties, from_slice};
+pub use diagnostic::Span;
+pub use dump::{LineEnding, Separator, WriteOptions};
+pub use load::{Duplicate, DuplicatePolicy, Encoding, EscapeMode, ParseOptions};
+pub use resolve::{EnvLookup, Lookup, Lookups, ResolveError};
+#[cfg(feature = "serde")]
+pub use ser::{to_properties, to_string};
+pub use typed::GetError;
 
 /// A type representing a properties parse error
 #[derive(thiserror::Error, Debug)]
 #[non_exhaustive]
 pub enum Error {
     /// A line did not have proper formatting
-    #[error("line {0} is malformed \"{1}\"")]
-    MalformedLine(usize, String),
+    #[error("line {} is malformed \"{1}\"", .0.line)]
+    MalformedLine(Span, String),
 
     /// A line found does not contain proper utf-8
-    #[error("line {0} is not proper utf-8 \"{1}\"")]
-    InvalidUtf8(usize, std::str::Utf8Error),
+    #[error("line {} is not proper utf-8 \"{1}\"", .0.line)]
+    InvalidUtf8(Span, std::str::Utf8Error),
+
+    /// Invalid escape sequence used, along with the sequence
+    #[error("line {} has invalid escape sequence \"{1}\"", .0.line)]
+    InvalidEscape(Span, String),
+
+    /// A `\u` escape is half of a surrogate pair without the other half
+    #[error("line {} column {} has an unpaired surrogate \\u{1:04X}", .0.line, .0.column)]
+    UnpairedSurrogate(Span, u16),
+
+    /// A key was defined again, along with the key and the line it was first defined on
+    #[error("line {} defines \"{1}\" again, it was first defined on line {2}", .0.line)]
+    DuplicateKey(Span, String, usize),
+
+    /// Reading the properties failed
+    #[error("failed to read properties: {0}")]
+    Io(#[from] std::io::Error),
 
-    /// Invalid escape sequence used
-    #[error("line {0} has invalid escape sequence \"{1}\"")]
-    InvalidEscape(usize, String),
+    /// Converting between properties and a type failed
+    #[cfg(feature = "serde")]
+    #[error("{}{message}", location(.key, .line))]
+    Serde {
+        /// The key being converted, if known
+        key: Option<String>,
+
+        /// The line the key was read from, if known
+        line: Option<usize>,
+
+        /// What went wrong
+        message: String,
+    },
+}
+
+#[cfg(feature = "serde")]
+fn location(key: &Option<String>, line: &Option<usize>) -> String {
+    match (key, line) {
+        (Some(key), Some(line)) => format!("key \"{key}\" on line {line}: "),
+        (Some(key), None) => format!("key \"{key}\": "),
+        (None, _) => String::new(),
+    }
+}
+
+/// Names the line something was on for error messages, if it is known
+pub(crate) fn on_line(line: &Option<usize>) -> String {
+    match line {
+        Some(line) => format!(" on line {line}"),
+        None => String::new(),
+    }
 }
 
 /// The abstract notion of a properties file
 ///
-/// This uses a naive implementation but should be performant enough for most cases
+/// This uses a naive implementation but should be performant enough for most cases,
+/// see [`Properties::indexed`] for large sets of properties.
 #[derive(Debug, Default)]
 pub struct Properties<'bytes> {
     pairs: Vec<(Cow<'bytes, str>, Cow<'bytes, str>)>,
+    lines: Vec<Option<usize>>,
+    encoding: Option<Encoding>,
+    line_ending: Option<LineEnding>,
+    index: Option<OnceLock<KeyIndex>>,
+}
+
+/// Positions of every pair by key, in insertion order
+#[derive(Debug, Default)]
+struct KeyIndex {
+    positions: HashMap<String, Vec<usize>>,
+
+    /// The distinct keys in order so ones sharing a prefix are together, built on the first prefix lookup
+    sorted: OnceLock<Vec<String>>,
 }
 
 impl<'bytes> Properties<'bytes> {
     /// Attempts parsing properties content
     pub fn load(content: &'bytes [u8]) -> Result<Self, Error> {
-        load::load(content)
+        load::load(content, &ParseOptions::default())
+    }
+
+    /// Attempts parsing properties content according to the options
+    pub fn load_with(content: &'bytes [u8], opts: &ParseOptions) -> Result<Self, Error> {
+        load::load(content, opts)
+    }
+
+    /// Parses as much of the content as possible, skipping the entries that can not be parsed
+    ///
+    /// Gives back every error found along with the pairs that could be parsed.
+    pub fn load_lenient(content: &'bytes [u8]) -> (Self, Vec<Error>) {
+        load::load_lenient(content, &ParseOptions::default())
+    }
+
+    /// Parses as much of the content as possible according to the options
+    pub fn load_lenient_with(content: &'bytes [u8], opts: &ParseOptions) -> (Self, Vec<Error>) {
+        load::load_lenient(content, opts)
+    }
+
+    /// Parses properties from a reader without needing all of the content at once
+    pub fn from_reader(reader: impl std::io::BufRead) -> Result<Properties<'static>, Error> {
+        let mut parser = reader::Parser::new(reader);
+        let mut props = Properties::default();
+
+        while let Some(pair) = parser.next_pair() {
+            let (k, v, line) = pair?;
+            props.push(Cow::Owned(k), Cow::Owned(v), Some(line));
+        }
+
+        props.line_ending = parser.line_ending();
+
+        Ok(props)
+    }
+
+    /// The encoding the properties were loaded with, if they were loaded
+    pub fn encoding(&self) -> Option<Encoding> {
+        self.encoding
+    }
+
+    /// The line ending the properties were read with, taken from the first line break
+    ///
+    /// Formatting with [`std::fmt::Display`] keeps it, pass it in [`WriteOptions::line_ending`]
+    /// to keep it with other options.
+    pub fn line_ending(&self) -> Option<LineEnding> {
+        self.line_ending
+    }
+
+    /// Uses a hash index for lookups and a sorted one for [`Properties::prefix`] instead of scanning every pair
+    ///
+    /// The index is built on the first lookup and rebuilt after changes that could invalidate it.
+    pub fn indexed(mut self) -> Self {
+        self.index = Some(OnceLock::new());
+        self
     }
 
     /// returns how many pairs
@@ -57,13 +191,9 @@ impl<'bytes> Properties<'bytes> {
     where
         'container: 'bytes,
     {
-        for (k, v) in &self.pairs {
-            if k == key {
-                return Some(v.borrow());
-            }
-        }
+        let pos = self.position(key)?;
 
-        None
+        Some(self.pairs[pos].1.borrow())
     }
 
     /// Get all values for a specific key
@@ -71,43 +201,236 @@ impl<'bytes> Properties<'bytes> {
     where
         'container: 'bytes,
     {
-        let mut res = Vec::new();
-
-        for (k, v) in &self.pairs {
-            if k == key {
-                res.push(v.borrow());
-            }
-        }
+        self.positions(key)
+            .into_iter()
+            .map(|i| self.pairs[i].1.borrow())
+            .collect()
+    }
 
-        res
+    /// Get the line the first matching key was read from, if it was read from anywhere
+    pub fn line(&self, key: &str) -> Option<usize> {
+        self.lines[self.position(key)?]
     }
 
     /// Insert owned strings into these properties
     pub fn insert(&mut self, key: String, value: String) {
-        self.pairs.push((Cow::Owned(key), Cow::Owned(value)));
+        self.push(Cow::Owned(key), Cow::Owned(value), None);
     }
 
     /// Insert borrowed strings into these properties
     pub fn insert_str(&mut self, key: &'bytes str, value: &'bytes str) {
-        self.pairs.push((Cow::Borrowed(key), Cow::Borrowed(value)));
+        self.push(Cow::Borrowed(key), Cow::Borrowed(value), None);
     }
 
     /// Deletes all the properties that match the keys
     pub fn delete(&mut self, key: &str) {
-        for i in (0..self.pairs.len()).rev() {
-            if self.pairs[i].0 == key {
-                self.pairs.remove(i);
+        self.retain(|k, _| k != key);
+    }
+
+    /// Sets the value of the first pair with the key where it is, removing any later duplicates
+    ///
+    /// Appends a new pair if there is none, gives back the value that was replaced.
+    pub fn set(
+        &mut self,
+        key: impl Into<Cow<'bytes, str>>,
+        value: impl Into<Cow<'bytes, str>>,
+    ) -> Option<Cow<'bytes, str>> {
+        let key = key.into();
+
+        let Some(pos) = self.position(&key) else {
+            self.push(key, value.into(), None);
+            return None;
+        };
+
+        let old = std::mem::replace(&mut self.pairs[pos].1, value.into());
+
+        if self.positions(&key).len() > 1 {
+            let mut i = 0;
+
+            self.retain(|k, _| {
+                i += 1;
+                i - 1 <= pos || k != key
+            });
+        }
+
+        Some(old)
+    }
+
+    /// Sets the value of every pair with the key, giving back how many there were
+    pub fn replace_all(&mut self, key: &str, value: impl Into<Cow<'bytes, str>>) -> usize {
+        let value = value.into();
+        let positions = self.positions(key);
+
+        for pos in &positions {
+            self.pairs[*pos].1 = value.clone();
+        }
+
+        positions.len()
+    }
+
+    /// Renames every pair with the key, giving back how many there were
+    pub fn rename_key(&mut self, key: &str, to: impl Into<Cow<'bytes, str>>) -> usize {
+        let to = to.into();
+        let positions = self.positions(key);
+
+        for pos in &positions {
+            self.pairs[*pos].0 = to.clone();
+        }
+
+        if !positions.is_empty() {
+            self.invalidate();
+        }
+
+        positions.len()
+    }
+
+    /// Inserts a pair right before the first pair with the key `before`
+    ///
+    /// Nothing is inserted if there is no such pair, which gives back false.
+    pub fn insert_before(
+        &mut self,
+        before: &str,
+        key: impl Into<Cow<'bytes, str>>,
+        value: impl Into<Cow<'bytes, str>>,
+    ) -> bool {
+        match self.position(before) {
+            Some(pos) => {
+                self.insert_at(pos, key.into(), value.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts a pair right after the first pair with the key `after`
+    ///
+    /// Nothing is inserted if there is no such pair, which gives back false.
+    pub fn insert_after(
+        &mut self,
+        after: &str,
+        key: impl Into<Cow<'bytes, str>>,
+        value: impl Into<Cow<'bytes, str>>,
+    ) -> bool {
+        match self.position(after) {
+            Some(pos) => {
+                self.insert_at(pos + 1, key.into(), value.into());
+                true
             }
+            None => false,
         }
     }
 
+    /// Keeps only the pairs `keep` is true for
+    pub fn retain(&mut self, mut keep: impl FnMut(&str, &str) -> bool) {
+        let kept = self
+            .pairs
+            .iter()
+            .map(|(k, v)| keep(k, v))
+            .collect::<Vec<_>>();
+
+        let mut it = kept.iter();
+        self.pairs.retain(|_| *it.next().unwrap_or(&true));
+
+        let mut it = kept.iter();
+        self.lines.retain(|_| *it.next().unwrap_or(&true));
+
+        self.invalidate();
+    }
+
+    /// The first pair with the key, for editing it or adding it if there is none
+    pub fn entry(&mut self, key: impl Into<Cow<'bytes, str>>) -> entry::Entry<'_, 'bytes> {
+        entry::Entry::new(self, key.into())
+    }
+
     /// Merge a different properties into this one
+    ///
+    /// The pairs are appended, so lookups still find the ones already here first,
+    /// see [`layered::Layered`] for giving other properties precedence.
     pub fn merge<'other>(&mut self, other: Properties<'other>)
     where
         'other: 'bytes,
     {
-        for (k, v) in other.pairs {
-            self.pairs.push((k, v));
+        for ((k, v), line) in other.pairs.into_iter().zip(other.lines) {
+            self.push(k, v, line);
+        }
+    }
+
+    /// A view of the pairs with keys under `prefix`, with keys relative to it
+    pub fn prefix<'a>(&'a self, prefix: &str) -> prefix::Prefix<'a, 'bytes> {
+        prefix::Prefix::new(self, prefix)
+    }
+
+    /// New properties with the pairs under `prefix`, with keys relative to it
+    pub fn strip_prefix(&self, prefix: &str) -> Properties<'bytes> {
+        let mut props = self.prefix(prefix).to_properties();
+
+        props.encoding = self.encoding;
+        props.line_ending = self.line_ending;
+
+        if self.index.is_some() {
+            props = props.indexed();
+        }
+
+        props
+    }
+
+    /// Every key that has more than one pair, in the order they first appear
+    ///
+    /// Only finds anything when the duplicates were kept, which is the default.
+    pub fn duplicates(&self) -> Vec<Duplicate<'_>> {
+        let mut positions = HashMap::<&str, Vec<usize>>::new();
+
+        for (i, (k, _)) in self.pairs.iter().enumerate() {
+            positions.entry(k).or_default().push(i);
+        }
+
+        let mut duplicates = positions
+            .into_values()
+            .filter(|positions| positions.len() > 1)
+            .collect::<Vec<_>>();
+
+        duplicates.sort_unstable_by_key(|positions| positions[0]);
+
+        duplicates
+            .into_iter()
+            .map(|positions| Duplicate {
+                key: &self.pairs[positions[0]].0,
+                values: positions
+                    .iter()
+                    .map(|pos| self.pairs[*pos].1.as_ref())
+                    .collect(),
+                lines: positions.iter().map(|pos| self.lines[*pos]).collect(),
+            })
+            .collect()
+    }
+
+    /// Expands `${key}` references in every value
+    ///
+    /// `${key:default}` falls back to `default` when `key` is not defined, references may be
+    /// nested in keys and defaults, and `$${` is read as a literal `${`.
+    pub fn resolve(&self) -> Result<Properties<'static>, ResolveError> {
+        resolve::resolve(self, &Lookups::default())
+    }
+
+    /// Expands references like [`Properties::resolve`], with `${prefix:name}` going through `lookups`
+    ///
+    /// The properties themselves are left as they are.
+    pub fn resolve_with(&self, lookups: &Lookups) -> Result<Properties<'static>, ResolveError> {
+        resolve::resolve(self, lookups)
+    }
+
+    /// Writes the properties out, formatted according to the options
+    pub fn write_to(&self, w: impl std::io::Write, opts: &WriteOptions) -> std::io::Result<()> {
+        let mut adapter = IoAdapter {
+            inner: w,
+            error: None,
+        };
+
+        match dump::write(self, &mut adapter, opts) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(adapter
+                .error
+                .unwrap_or_else(|| std::io::Error::other("formatting properties failed"))),
         }
     }
 
@@ -122,16 +445,123 @@ impl<'bytes> Properties<'bytes> {
     }
 }
 
+impl<'bytes> Properties<'bytes> {
+    pub(crate) fn push(
+        &mut self,
+        key: Cow<'bytes, str>,
+        value: Cow<'bytes, str>,
+        line: Option<usize>,
+    ) {
+        if let Some(index) = self.index.as_mut().and_then(OnceLock::get_mut) {
+            index
+                .positions
+                .entry(key.to_string())
+                .or_default()
+                .push(self.pairs.len());
+
+            index.sorted.take();
+        }
+
+        self.pairs.push((key, value));
+        self.lines.push(line);
+    }
+
+    fn insert_at(&mut self, pos: usize, key: Cow<'bytes, str>, value: Cow<'bytes, str>) {
+        self.pairs.insert(pos, (key, value));
+        self.lines.insert(pos, None);
+        self.invalidate();
+    }
+
+    /// Position of the first pair with the key
+    pub(crate) fn position(&self, key: &str) -> Option<usize> {
+        match self.key_index() {
+            Some(index) => index.positions.get(key).map(|pos| pos[0]),
+            None => self.pairs.iter().position(|(k, _)| k == key),
+        }
+    }
+
+    /// Positions of every pair with the key
+    pub(crate) fn positions(&self, key: &str) -> Vec<usize> {
+        match self.key_index() {
+            Some(index) => index.positions.get(key).cloned().unwrap_or_default(),
+            None => (0..self.pairs.len())
+                .filter(|i| self.pairs[*i].0 == key)
+                .collect(),
+        }
+    }
+
+    /// Positions of every pair whose key starts with `prefix`, in insertion order
+    pub(crate) fn prefixed(&self, prefix: &str) -> Vec<usize> {
+        match self.key_index() {
+            Some(index) => {
+                let sorted = index.sorted.get_or_init(|| {
+                    let mut keys = index.positions.keys().cloned().collect::<Vec<_>>();
+                    keys.sort_unstable();
+                    keys
+                });
+
+                let start = sorted.partition_point(|k| k.as_str() < prefix);
+
+                let mut found = sorted[start..]
+                    .iter()
+                    .take_while(|k| k.starts_with(prefix))
+                    .flat_map(|k| index.positions[k].iter().copied())
+                    .collect::<Vec<_>>();
+
+                found.sort_unstable();
+                found
+            }
+            None => (0..self.pairs.len())
+                .filter(|i| self.pairs[*i].0.starts_with(prefix))
+                .collect(),
+        }
+    }
+
+    /// The index if one is used, building it if needed
+    fn key_index(&self) -> Option<&KeyIndex> {
+        let index = self.index.as_ref()?;
+
+        Some(index.get_or_init(|| {
+            let mut index = KeyIndex::default();
+
+            for (i, (k, _)) in self.pairs.iter().enumerate() {
+                index.positions.entry(k.to_string()).or_default().push(i);
+            }
+
+            index
+        }))
+    }
+
+    /// Drops the index so it gets rebuilt on the next lookup
+    pub(crate) fn invalidate(&mut self) {
+        if let Some(index) = &mut self.index {
+            index.take();
+        }
+    }
+}
+
 impl<'bytes> std::ops::Index<&str> for Properties<'bytes> {
     type Output = str;
 
     fn index(&self, index: &str) -> &Self::Output {
-        for (k, v) in &self.pairs {
-            if k == index {
-                return v.borrow();
-            }
+        match self.get(index) {
+            Some(v) => v,
+            None => panic!("properties does not have {index}"),
         }
+    }
+}
+
+/// Lets formatting write straight into an [`std::io::Write`]
+struct IoAdapter<W> {
+    inner: W,
+    error: Option<std::io::Error>,
+}
 
-        panic!("properties does not have {index}")
+impl<W: std::io::Write> std::fmt::Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            std::fmt::Error
+        })
     }
 }
@@ -1,252 +1,624 @@
-use crate::{Error, Properties};
+use crate::{Error, LineEnding, Properties, Span};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
 
-pub(crate) fn load<'bytes>(mut content: &'bytes [u8]) -> Result<Properties<'bytes>, Error> {
-    let mut pairs = Vec::new();
+pub(crate) type Pair<'bytes> = (Cow<'bytes, str>, Cow<'bytes, str>);
+
+/// The logical line of an entry continued over several lines so far and where its pieces came from
+pub(crate) type Partial = Option<(Vec<u8>, Vec<Piece>)>;
+
+/// Where a line, or the part of a logical line taken from it, is in the content
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Piece {
+    /// Where it starts in the logical line
+    start: usize,
+
+    /// Byte offset in the content
+    offset: usize,
+
+    line: usize,
+
+    /// The column of its first character
+    column: usize,
+
+    /// It was transcoded from ISO-8859-1, so every character was a single byte
+    latin1: bool,
+}
+
+impl Piece {
+    /// The start of a line of the content
+    pub(crate) fn line(offset: usize, line: usize) -> Self {
+        Piece {
+            start: 0,
+            offset,
+            line,
+            column: 1,
+            latin1: false,
+        }
+    }
+
+    /// Skips over leading whitespace, which is always a byte per character
+    fn skip(self, len: usize) -> Self {
+        Piece {
+            offset: self.offset + len,
+            column: self.column + len,
+            ..self
+        }
+    }
+}
+
+/// Options for loading properties
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// How the content is encoded
+    pub encoding: Encoding,
+
+    /// How escape sequences are handled
+    pub escapes: EscapeMode,
+
+    /// What to do with keys defined more than once
+    pub duplicates: DuplicatePolicy,
+}
+
+/// What to do when a key is defined more than once
+///
+/// This applies when loading whole content, [`crate::reader::Parser`] gives every pair as it is read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keep every pair, lookups find the first one
+    #[default]
+    KeepAll,
+
+    /// Keep only the first pair
+    First,
+
+    /// Keep only the last value, where the first pair was, like java does
+    Last,
+
+    /// Report an [`Error::DuplicateKey`]
+    Error,
+}
+
+/// A key defined more than once, see [`Properties::duplicates`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate<'a> {
+    /// The key
+    pub key: &'a str,
+
+    /// Every value in order
+    pub values: Vec<&'a str>,
+
+    /// The line each value was read from, if it was read from anywhere
+    pub lines: Vec<Option<usize>>,
+}
+
+/// How to treat backslashes before characters that have no special meaning
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EscapeMode {
+    /// Drop the backslash like java does, so `\q` is read as `q`
+    #[default]
+    Java,
+
+    /// Report an [`Error::InvalidEscape`]
+    Strict,
+}
+
+/// The character encoding of properties content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, the default
+    #[default]
+    Utf8,
+
+    /// ISO-8859-1, what java's `Properties.load(InputStream)` reads
+    Latin1,
+
+    /// UTF-8 unless the content is not valid UTF-8, then ISO-8859-1
+    ///
+    /// This follows java's `PropertyResourceBundle`,
+    /// [`Properties::encoding`] tells which one was used.
+    Auto,
+}
+
+pub(crate) fn load<'bytes>(
+    content: &'bytes [u8],
+    opts: &ParseOptions,
+) -> Result<Properties<'bytes>, Error> {
+    parse(content, opts, Err)
+}
+
+/// Loads everything that can be, collecting the errors of what could not be
+pub(crate) fn load_lenient<'bytes>(
+    content: &'bytes [u8],
+    opts: &ParseOptions,
+) -> (Properties<'bytes>, Vec<Error>) {
+    let mut errors = Vec::new();
+
+    let props = parse(content, opts, |e| {
+        errors.push(e);
+        Ok(())
+    });
+
+    // every error was collected, so parsing itself can not fail
+    (props.unwrap_or_default(), errors)
+}
+
+/// Parses the content, handing errors to `recover` which decides whether to keep going
+fn parse<'bytes>(
+    mut content: &'bytes [u8],
+    opts: &ParseOptions,
+    mut recover: impl FnMut(Error) -> Result<(), Error>,
+) -> Result<Properties<'bytes>, Error> {
+    let encoding = match opts.encoding {
+        Encoding::Auto if std::str::from_utf8(content).is_ok() => Encoding::Utf8,
+        Encoding::Auto => Encoding::Latin1,
+        encoding => encoding,
+    };
+
+    let mut props = Properties {
+        encoding: Some(encoding),
+        ..Default::default()
+    };
+
+    let mut offset = 0;
     let mut line = 0;
-    let mut partial = None::<(&'bytes str, String)>;
+    let mut partial = None;
 
-    while !content.is_empty() {
-        let mut cur = match memchr::memchr2(b'\n', b'\r', content) {
-            Some(br) => {
-                let (cur, next) = content.split_at(br);
-                content = &next[1..];
-                cur
+    // positions of the keys so far, only needed when duplicates are not all kept
+    let mut seen = HashMap::new();
+
+    let mut add = |props: &mut Properties<'bytes>, (key, value): Pair<'bytes>, span: Span| {
+        if opts.duplicates == DuplicatePolicy::KeepAll {
+            props.push(key, value, Some(span.line));
+            return Ok(());
+        }
+
+        let Some(&pos) = seen.get(&key) else {
+            seen.insert(key.clone(), props.len());
+            props.push(key, value, Some(span.line));
+            return Ok(());
+        };
+
+        match opts.duplicates {
+            DuplicatePolicy::Last => {
+                props.pairs[pos].1 = value;
+                props.lines[pos] = Some(span.line);
             }
 
-            None => {
-                let next = content;
-                content = &content[content.len()..];
-                next
+            DuplicatePolicy::Error => {
+                let first = props.lines[pos].unwrap_or_default();
+                return Err(Error::DuplicateKey(span, key.into_owned(), first));
             }
-        };
+
+            _ => (),
+        }
+
+        Ok(())
+    };
+
+    while !content.is_empty() {
+        let (cur, full) = next_line(&mut content);
 
         line += 1;
 
-        if let Some(comment) = memchr::memchr2(b'#', b'!', cur) {
-            cur = &cur[..comment];
+        if props.line_ending.is_none() {
+            props.line_ending = LineEnding::of(full);
         }
 
-        if let Some((key, mut value)) = partial {
-            if odd_backslash(cur) {
-                let ext = std::str::from_utf8(&cur[0..cur.len() - 1])
-                    .map_err(|e| Error::InvalidUtf8(line, e))?
-                    .trim();
+        let at = Piece::line(offset, line);
 
-                value.push_str(ext);
+        let res = match parse_encoded(&mut partial, cur, at, encoding, opts) {
+            Ok(Some((pair, span))) => add(&mut props, pair, span),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
 
-                partial = Some((key, value));
-            } else {
-                let ext = std::str::from_utf8(&cur[0..cur.len()])
-                    .map_err(|e| Error::InvalidUtf8(line, e))?
-                    .trim();
+        if let Err(e) = res {
+            recover(e)?;
+        }
+
+        offset += full.len();
+    }
 
-                value.push_str(ext);
+    let res = match finish(&mut partial, opts) {
+        Ok(Some((pair, span))) => add(&mut props, pair, span),
+        Ok(None) => Ok(()),
+        Err(e) => Err(e),
+    };
 
-                pairs.push((Cow::Borrowed(key), Cow::Owned(value)));
-                partial = None;
-            }
-        } else {
-            if cur.is_empty() {
-                continue;
-            }
+    if let Err(e) = res {
+        recover(e)?;
+    }
 
-            let Some(assign) = memchr::memchr2(b'=', b':', cur) else {
-                let s = std::str::from_utf8(cur).map_err(|e| Error::InvalidUtf8(line, e))?;
+    Ok(props)
+}
 
-                return Err(Error::MalformedLine(line, s.to_string()));
-            };
+/// Parses a line in an encoding that has already been decided on
+pub(crate) fn parse_encoded<'bytes>(
+    partial: &mut Partial,
+    cur: &'bytes [u8],
+    at: Piece,
+    encoding: Encoding,
+    opts: &ParseOptions,
+) -> Result<Option<(Pair<'bytes>, Span)>, Error> {
+    if encoding == Encoding::Latin1 && !cur.is_ascii() {
+        let utf8 = cur.iter().map(|ch| *ch as char).collect::<String>();
+        let at = Piece { latin1: true, ..at };
+
+        let res = parse_line(partial, utf8.as_bytes(), at, opts)?;
+
+        Ok(res.map(|(pair, span)| (owned(pair), span)))
+    } else {
+        parse_line(partial, cur, at, opts)
+    }
+}
 
-            let (pre, post) = cur.split_at(assign);
+/// Parses a single line, giving back a pair and where its key is once one is complete
+///
+/// Lines ending in a continuation are collected into one logical line first,
+/// so keys and escapes may be spread over several lines.
+fn parse_line<'bytes>(
+    partial: &mut Partial,
+    cur: &'bytes [u8],
+    at: Piece,
+    opts: &ParseOptions,
+) -> Result<Option<(Pair<'bytes>, Span)>, Error> {
+    let trimmed = trim_start(cur);
+    let at = at.skip(cur.len() - trimmed.len());
+    let cur = trimmed;
+
+    if let Some((mut logical, mut pieces)) = partial.take() {
+        // like java, a blank line ending a continuation of nothing is not an entry
+        if logical.is_empty() && cur.is_empty() {
+            return Ok(None);
+        }
 
-            let key = std::str::from_utf8(pre)
-                .map_err(|e| Error::InvalidUtf8(line, e))?
-                .trim();
+        pieces.push(Piece {
+            start: logical.len(),
+            ..at
+        });
 
-            if odd_backslash(post) {
-                let value = std::str::from_utf8(&post[1..])
-                    .map_err(|e| Error::InvalidUtf8(line, e))?
-                    .trim();
+        if odd_backslash(cur) {
+            logical.extend_from_slice(&cur[..cur.len() - 1]);
+            *partial = Some((logical, pieces));
 
-                let mut value = value.to_string();
-                value.pop();
+            return Ok(None);
+        }
 
-                partial = Some((key, value));
-            } else {
-                let val = match escape(&post[1..]) {
-                    Some(buf) => buf,
-                    None => {
-                        let s =
-                            std::str::from_utf8(cur).map_err(|e| Error::InvalidUtf8(line, e))?;
-                        return Err(Error::InvalidEscape(line, s.to_string()));
-                    }
-                };
+        logical.extend_from_slice(cur);
 
-                let value = match val {
-                    Cow::Owned(bytes) => {
-                        let mut string = String::from_utf8(bytes)
-                            .map_err(|e| Error::InvalidUtf8(line, e.utf8_error()))?;
+        let (pair, span) = parse_logical(&logical, &pieces, opts)?;
 
-                        string = string.trim().to_string(); // not the most efficient thing
+        return Ok(Some((owned(pair), span)));
+    }
 
-                        Cow::Owned(string)
-                    }
+    if cur.is_empty() || is_comment(cur) {
+        return Ok(None);
+    }
 
-                    Cow::Borrowed(bytes) => Cow::Borrowed(
-                        std::str::from_utf8(bytes)
-                            .map_err(|e| Error::InvalidUtf8(line, e))?
-                            .trim(),
-                    ),
-                };
+    if odd_backslash(cur) {
+        *partial = Some((cur[..cur.len() - 1].to_vec(), vec![at]));
 
-                pairs.push((Cow::Borrowed(key), value));
-            }
-        }
+        return Ok(None);
     }
 
-    Ok(Properties { pairs })
+    Ok(Some(parse_logical(cur, &[at], opts)?))
 }
 
-fn odd_backslash(line: &[u8]) -> bool {
-    let mut cnt = 0;
+/// Completes an entry still being continued when the content ends
+pub(crate) fn finish(
+    partial: &mut Partial,
+    opts: &ParseOptions,
+) -> Result<Option<(Pair<'static>, Span)>, Error> {
+    match partial.take() {
+        Some((logical, pieces)) => {
+            let (pair, span) = parse_logical(&logical, &pieces, opts)?;
 
-    for ch in line.iter().rev() {
-        if *ch == b'\\' {
-            cnt += 1
-        } else {
-            break;
+            Ok(Some((owned(pair), span)))
         }
+        None => Ok(None),
     }
+}
 
-    cnt & 1 == 1
+/// Splits a complete logical line without leading whitespace into its key and value
+///
+/// Gives back where the key is along with them.
+fn parse_logical<'bytes>(
+    cur: &'bytes [u8],
+    pieces: &[Piece],
+    opts: &ParseOptions,
+) -> Result<(Pair<'bytes>, Span), Error> {
+    let (key_end, value_start) = split(cur);
+    let key_end = trim_end(&cur[..key_end]).len();
+    let value_end = value_start + trim_end(&cur[value_start..]).len();
+
+    let key = decode(cur, 0..key_end, pieces, opts)?;
+    let value = decode(cur, value_start..value_end, pieces, opts)?;
+
+    Ok(((key, value), span(cur, pieces, 0..key_end)))
 }
 
-fn escape(line: &[u8]) -> Option<Cow<[u8]>> {
-    if line.is_empty() {
-        return Some(Cow::Borrowed(line));
-    }
+fn owned(pair: Pair<'_>) -> Pair<'static> {
+    (
+        Cow::Owned(pair.0.into_owned()),
+        Cow::Owned(pair.1.into_owned()),
+    )
+}
+
+/// Splits the next line off of `content`, giving it both without and with its line break
+///
+/// `\r\n`, `\n` and `\r` each end a line.
+pub(crate) fn next_line<'bytes>(content: &mut &'bytes [u8]) -> (&'bytes [u8], &'bytes [u8]) {
+    let (len, br) = match memchr::memchr2(b'\n', b'\r', content) {
+        Some(br) if content[br..].starts_with(b"\r\n") => (br, br + 2),
+        Some(br) => (br, br + 1),
+        None => (content.len(), content.len()),
+    };
+
+    let (full, rest) = content.split_at(br);
+    *content = rest;
+
+    (&full[..len], full)
+}
+
+/// Checks if a line without leading whitespace is a comment
+///
+/// Only whole lines can be comments, a `#` or `!` later on is part of the value.
+pub(crate) fn is_comment(line: &[u8]) -> bool {
+    matches!(line.first(), Some(b'#' | b'!'))
+}
 
-    let last = line.len() - 1;
+/// Finds where the key ends and the value starts in a line without leading whitespace
+///
+/// The key ends at the first unescaped `=`, `:` or whitespace, which may be
+/// surrounded by more whitespace. A line with only a key has an empty value.
+pub(crate) fn split(line: &[u8]) -> (usize, usize) {
+    let mut escaped = false;
+
+    let key_end = line
+        .iter()
+        .position(|ch| {
+            if escaped {
+                escaped = false;
+                false
+            } else if *ch == b'\\' {
+                escaped = true;
+                false
+            } else {
+                matches!(ch, b'=' | b':') || is_whitespace(*ch)
+            }
+        })
+        .unwrap_or(line.len());
 
-    if !line[0..last].contains(&b'\\') {
-        return Some(Cow::Borrowed(line));
+    let mut value = line.len() - trim_start(&line[key_end..]).len();
+
+    if matches!(line.get(value), Some(b'=' | b':')) {
+        value = line.len() - trim_start(&line[value + 1..]).len();
     }
 
-    let mut vec = Vec::with_capacity(line.len());
-    let mut it = 0;
+    (key_end, value)
+}
 
-    while it < last {
-        let ch = line[it];
+/// Unescapes and validates the key or value in `range` of the logical line `cur`
+fn decode<'bytes>(
+    cur: &'bytes [u8],
+    range: Range<usize>,
+    pieces: &[Piece],
+    opts: &ParseOptions,
+) -> Result<Cow<'bytes, str>, Error> {
+    let raw = &cur[range.clone()];
 
-        if ch == b'\\' {
-            it += 1;
-            let ch = line[it];
-
-            match ch {
-                b'r' => vec.push(b'\r'),
-                b'n' => vec.push(b'\n'),
-                b't' => vec.push(b'\t'),
-                b'\\' => vec.push(b'\\'),
-                b'u' => {
-                    if it + 5 > line.len() {
-                        return None;
-                    }
+    let val = match unescape(raw, opts.escapes) {
+        Ok(val) => val,
+
+        Err(Escape::Invalid(escape)) => {
+            let escape = range.start + escape.start..range.start + escape.end;
+            let sequence = String::from_utf8_lossy(&cur[escape.clone()]).into_owned();
 
-                    it += 1;
+            return Err(Error::InvalidEscape(span(cur, pieces, escape), sequence));
+        }
 
-                    let mut buf = [0, 0, 0, 0];
+        Err(Escape::UnpairedSurrogate(offset, code)) => {
+            let escape = range.start + offset..range.start + offset + 6;
 
-                    let len = unicode_encode(
-                        [line[it], line[it + 1], line[it + 2], line[it + 3]],
-                        &mut buf,
-                    );
+            return Err(Error::UnpairedSurrogate(span(cur, pieces, escape), code));
+        }
+    };
 
-                    if len == 0 {
-                        return None;
-                    }
+    let invalid = |e: std::str::Utf8Error| {
+        // unescaping keeps invalid utf-8 as is, so it can be found in the raw bytes
+        let e = std::str::from_utf8(raw).err().unwrap_or(e);
+        let start = range.start + e.valid_up_to();
+        let end = e.error_len().map_or(range.end, |len| start + len);
 
-                    for ch in &buf[0..len] {
-                        vec.push(*ch);
-                    }
+        Error::InvalidUtf8(span(cur, pieces, start..end), e)
+    };
+
+    let value = match val {
+        Cow::Owned(bytes) => {
+            Cow::Owned(String::from_utf8(bytes).map_err(|e| invalid(e.utf8_error()))?)
+        }
 
-                    it += 3; // last line in loop brings it to 4
-                }
+        Cow::Borrowed(bytes) => Cow::Borrowed(std::str::from_utf8(bytes).map_err(invalid)?),
+    };
 
-                _ => return None,
-            }
+    Ok(value)
+}
+
+/// Finds where `range` of the logical line `cur` made of `pieces` is in the content
+fn span(cur: &[u8], pieces: &[Piece], range: Range<usize>) -> Span {
+    let locate = |at: usize| {
+        let piece = pieces
+            .iter()
+            .rev()
+            .find(|p| p.start <= at)
+            .unwrap_or(&pieces[0]);
+        let chars = String::from_utf8_lossy(&cur[piece.start..at])
+            .chars()
+            .count();
+        let len = if piece.latin1 {
+            chars
         } else {
-            vec.push(ch);
-        }
+            at - piece.start
+        };
 
-        it += 1;
+        (piece.offset + len, piece.line, piece.column + chars)
+    };
+
+    let (offset, line, column) = locate(range.start);
+    let (end, _, _) = locate(range.end);
+
+    Span {
+        offset,
+        len: end.saturating_sub(offset),
+        line,
+        column,
     }
+}
+
+/// whitespace as the spec defines it (space, tab and form feed)
+fn is_whitespace(ch: u8) -> bool {
+    matches!(ch, b' ' | b'\t' | b'\x0c')
+}
 
-    vec.push(line[last]);
+pub(crate) fn trim_start(line: &[u8]) -> &[u8] {
+    let start = line
+        .iter()
+        .position(|ch| !is_whitespace(*ch))
+        .unwrap_or(line.len());
 
-    Some(Cow::Owned(vec))
+    &line[start..]
 }
 
-fn unicode_encode(unicode: [u8; 4], out: &mut [u8; 4]) -> usize {
-    let mut code: u16 = 0;
+/// trims trailing whitespace, unless it has been escaped
+pub(crate) fn trim_end(mut line: &[u8]) -> &[u8] {
+    while let [rest @ .., last] = line {
+        if !is_whitespace(*last) || odd_backslash(rest) {
+            break;
+        }
 
-    let cur = hex(unicode[0]);
-    if cur == 0xff {
-        return 0;
+        line = rest;
     }
 
-    code |= cur as u16;
-    code <<= 4;
+    line
+}
+
+pub(crate) fn odd_backslash(line: &[u8]) -> bool {
+    let mut cnt = 0;
 
-    let cur = hex(unicode[1]);
-    if cur == 0xff {
-        return 0;
+    for ch in line.iter().rev() {
+        if *ch == b'\\' {
+            cnt += 1
+        } else {
+            break;
+        }
     }
 
-    code |= cur as u16;
-    code <<= 4;
+    cnt & 1 == 1
+}
 
-    let cur = hex(unicode[2]);
-    if cur == 0xff {
-        return 0;
-    }
+/// Why unescaping failed
+enum Escape {
+    /// An unknown or malformed escape sequence, with where it is
+    Invalid(Range<usize>),
 
-    code |= cur as u16;
-    code <<= 4;
+    /// A `\u` escape of half a surrogate pair, with its offset and code
+    UnpairedSurrogate(usize, u16),
+}
 
-    let cur = hex(unicode[3]);
-    if cur == 0xff {
-        return 0;
+fn unescape(line: &[u8], mode: EscapeMode) -> Result<Cow<'_, [u8]>, Escape> {
+    if memchr::memchr(b'\\', line).is_none() {
+        return Ok(Cow::Borrowed(line));
     }
 
-    code |= cur as u16;
+    let mut vec = Vec::with_capacity(line.len());
+    let mut it = 0;
 
-    if code & 0xFF80 == 0x0 {
-        out[0] = code as u8;
-        1
-    } else if code & 0xF800 == 0x0 {
-        out[1] = (code & 0x003F) as u8;
-        code >>= 6;
-        out[0] = (code & 0x001F) as u8;
+    while it < line.len() {
+        let ch = line[it];
+        it += 1;
 
-        out[0] |= 0xC0;
-        out[1] |= 0x80;
+        if ch != b'\\' {
+            vec.push(ch);
+            continue;
+        }
 
-        2
-    } else {
-        out[2] = (code & 0x003F) as u8;
-        code >>= 6;
+        let ch = *line.get(it).ok_or(Escape::Invalid(it - 1..it))?;
+        it += 1;
+
+        match ch {
+            b'r' => vec.push(b'\r'),
+            b'n' => vec.push(b'\n'),
+            b't' => vec.push(b'\t'),
+            b'f' => vec.push(b'\x0c'),
+            b'\\' | b'=' | b':' | b' ' | b'#' | b'!' => vec.push(ch),
+            b'u' => {
+                let start = it - 2;
+                let code = unicode(line.get(it..it + 4))
+                    .ok_or(Escape::Invalid(start..line.len().min(it + 4)))?;
+                it += 4;
+
+                let code = match code {
+                    0xD800..=0xDBFF => {
+                        let low = match line.get(it..it + 2) {
+                            Some(b"\\u") => unicode(line.get(it + 2..it + 6)),
+                            _ => None,
+                        };
+
+                        match low {
+                            Some(low @ 0xDC00..=0xDFFF) => {
+                                it += 6;
+                                0x10000 + ((code as u32 - 0xD800) << 10) + (low as u32 - 0xDC00)
+                            }
+
+                            _ => return Err(Escape::UnpairedSurrogate(start, code)),
+                        }
+                    }
+
+                    0xDC00..=0xDFFF => return Err(Escape::UnpairedSurrogate(start, code)),
+
+                    _ => code as u32,
+                };
+
+                let ch = char::from_u32(code).ok_or(Escape::Invalid(start..it))?;
+                let mut buf = [0; 4];
 
-        out[1] = (code & 0x003F) as u8;
-        code >>= 6;
+                vec.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+            }
 
-        out[0] = (code & 0x000F) as u8;
+            _ if mode == EscapeMode::Java => vec.push(ch),
+            _ => {
+                // include the whole character that was escaped
+                let end = it
+                    + line[it..]
+                        .iter()
+                        .take_while(|ch| **ch & 0xc0 == 0x80)
+                        .count();
 
-        out[0] |= 0xE0;
-        out[1] |= 0x80;
-        out[2] |= 0x80;
+                return Err(Escape::Invalid(it - 2..end));
+            }
+        }
+    }
+
+    Ok(Cow::Owned(vec))
+}
+
+/// Reads the four hex digits of a `\u` escape
+fn unicode(digits: Option<&[u8]>) -> Option<u16> {
+    let mut code = 0;
+
+    for ch in digits? {
+        let cur = hex(*ch);
+
+        if cur == 0xff {
+            return None;
+        }
+
+        code = code << 4 | cur as u16;
+    }
 
-        3
-    } // not handling mult unicode for now
+    Some(code)
 }
 
 fn hex(ch: u8) -> u8 {
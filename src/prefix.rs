@@ -0,0 +1,146 @@
+//!
+//! Views of the keys under a prefix
+//!
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::Properties;
+
+/// The pairs whose keys are under a prefix, with the prefix taken off of the keys
+///
+/// Made with [`Properties::prefix`], `db.primary` covers `db.primary.url` as `url`
+/// but neither `db.primary` itself nor `db.primaryx`.
+#[derive(Debug, Clone)]
+pub struct Prefix<'a, 'bytes> {
+    props: &'a Properties<'bytes>,
+
+    /// Length of the prefix including the `.` after it
+    len: usize,
+
+    /// Positions of the pairs in the properties
+    positions: Vec<usize>,
+}
+
+impl<'a, 'bytes> Prefix<'a, 'bytes> {
+    pub(crate) fn new(props: &'a Properties<'bytes>, prefix: &str) -> Self {
+        let prefix = prefix.trim_end_matches('.');
+
+        if prefix.is_empty() {
+            return Prefix {
+                props,
+                len: 0,
+                positions: (0..props.len()).collect(),
+            };
+        }
+
+        let prefix = format!("{prefix}.");
+
+        Prefix {
+            props,
+            len: prefix.len(),
+            positions: props.prefixed(&prefix),
+        }
+    }
+
+    /// returns how many pairs
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Checks if there are pairs under the prefix
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Get the value for the first key that matches the relative key
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.key_values().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    /// Get all values for a relative key
+    pub fn get_all(&self, key: &str) -> Vec<&'a str> {
+        self.key_values()
+            .filter(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// Iterate over relative keys and their values
+    pub fn key_values(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        let props = self.props;
+
+        self.positions.iter().map(move |pos| {
+            let (k, v) = &props.pairs[*pos];
+
+            (&k[self.len..], v.as_ref())
+        })
+    }
+
+    /// Iterate over relative keys
+    pub fn keys(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.key_values().map(|(k, _)| k)
+    }
+
+    /// The distinct first segments of the relative keys, in the order they first appear
+    ///
+    /// Under `db`, the keys `primary.url`, `primary.user` and `replica.url` give `primary` and `replica`.
+    pub fn children(&self) -> Vec<&'a str> {
+        let mut seen = HashSet::new();
+
+        self.keys()
+            .map(|k| k.split('.').next().unwrap_or(k))
+            .filter(|child| seen.insert(*child))
+            .collect()
+    }
+
+    /// The view of a prefix under this one
+    pub fn prefix(&self, prefix: &str) -> Prefix<'a, 'bytes> {
+        let prefix = prefix.trim_end_matches('.');
+
+        if prefix.is_empty() {
+            return self.clone();
+        }
+
+        let len = self.len + prefix.len() + 1;
+        let props = self.props;
+
+        let positions = self
+            .positions
+            .iter()
+            .copied()
+            .filter(|pos| {
+                let rest = &props.pairs[*pos].0[self.len..];
+
+                rest.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .collect();
+
+        Prefix {
+            props,
+            len,
+            positions,
+        }
+    }
+
+    /// Copies the pairs into new properties with the relative keys
+    ///
+    /// Borrowed keys and values stay borrowed.
+    pub fn to_properties(&self) -> Properties<'bytes> {
+        let mut props = Properties::default();
+
+        for pos in &self.positions {
+            let (k, v) = &self.props.pairs[*pos];
+
+            let key = match k {
+                Cow::Borrowed(k) => Cow::Borrowed(&k[self.len..]),
+                Cow::Owned(k) => Cow::Owned(k[self.len..].to_string()),
+            };
+
+            props.push(key, v.clone(), self.props.lines[*pos]);
+        }
+
+        props
+    }
+}
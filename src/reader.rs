@@ -0,0 +1,196 @@
+//!
+//! Incremental parsing from [`std::io::BufRead`]
+//!
+
+use std::io::{BufRead, ErrorKind};
+
+use crate::{load, Encoding, Error, LineEnding, ParseOptions};
+
+/// Parses properties line by line, yielding each pair as it is completed
+///
+/// Only the line being read (and an entry continued over several lines) is kept in memory.
+pub struct Parser<R> {
+    reader: R,
+    buf: Vec<u8>,
+    line: usize,
+    offset: usize,
+    consumed: usize,
+    partial: load::Partial,
+    opts: ParseOptions,
+    line_ending: Option<LineEnding>,
+    cr: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Parser<R> {
+    /// Creates a parser reading from `reader`
+    pub fn new(reader: R) -> Self {
+        Parser::with_options(reader, ParseOptions::default())
+    }
+
+    /// Creates a parser reading from `reader` according to the options
+    ///
+    /// Since the whole input is never available, [`Encoding::Auto`] is decided line by line.
+    pub fn with_options(reader: R, opts: ParseOptions) -> Self {
+        Parser {
+            reader,
+            buf: Vec::new(),
+            line: 0,
+            offset: 0,
+            consumed: 0,
+            partial: None,
+            opts,
+            line_ending: None,
+            cr: false,
+            done: false,
+        }
+    }
+
+    /// The line most recently read
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The line ending of the first line break, once it is known
+    pub fn line_ending(&self) -> Option<LineEnding> {
+        self.line_ending
+    }
+
+    /// Reads the next line into the buffer, returning false at the end of input
+    ///
+    /// `\r\n`, `\n` and `\r` each end a line, the `\n` after a `\r` is only
+    /// looked for when the next line is read so a `\r` never waits on more input.
+    fn read_line(&mut self) -> std::io::Result<bool> {
+        self.buf.clear();
+
+        let mut read = false;
+
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            if self.cr {
+                self.cr = false;
+
+                let crlf = available.first() == Some(&b'\n');
+
+                if self.line_ending.is_none() {
+                    self.line_ending = Some(if crlf {
+                        LineEnding::Crlf
+                    } else {
+                        LineEnding::Cr
+                    });
+                }
+
+                if crlf {
+                    self.reader.consume(1);
+                    self.consumed += 1;
+                    continue;
+                }
+            }
+
+            if !read {
+                self.offset = self.consumed;
+            }
+
+            if available.is_empty() {
+                return Ok(read);
+            }
+
+            read = true;
+
+            match memchr::memchr2(b'\n', b'\r', available) {
+                Some(br) => {
+                    self.buf.extend_from_slice(&available[..br]);
+
+                    if available[br] == b'\r' {
+                        self.cr = true;
+                    } else if self.line_ending.is_none() {
+                        self.line_ending = Some(LineEnding::Lf);
+                    }
+
+                    self.reader.consume(br + 1);
+                    self.consumed += br + 1;
+
+                    return Ok(true);
+                }
+
+                None => {
+                    let len = available.len();
+                    self.buf.extend_from_slice(available);
+                    self.reader.consume(len);
+                    self.consumed += len;
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Parser<R> {
+    /// Reads the next pair along with the line it started on
+    pub(crate) fn next_pair(&mut self) -> Option<Result<(String, String, usize), Error>> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            match self.read_line() {
+                Ok(true) => (),
+                Ok(false) => {
+                    self.done = true;
+
+                    return match load::finish(&mut self.partial, &self.opts) {
+                        Ok(pair) => pair.map(|((key, value), span)| {
+                            Ok((key.into_owned(), value.into_owned(), span.line))
+                        }),
+                        Err(e) => Some(Err(e)),
+                    };
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(Error::Io(e)));
+                }
+            }
+
+            self.line += 1;
+
+            let encoding = match self.opts.encoding {
+                Encoding::Auto if std::str::from_utf8(&self.buf).is_ok() => Encoding::Utf8,
+                Encoding::Auto => Encoding::Latin1,
+                encoding => encoding,
+            };
+
+            let res = load::parse_encoded(
+                &mut self.partial,
+                &self.buf,
+                load::Piece::line(self.offset, self.line),
+                encoding,
+                &self.opts,
+            );
+
+            match res {
+                Ok(Some(((key, value), span))) => {
+                    return Some(Ok((key.into_owned(), value.into_owned(), span.line)))
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Parser<R> {
+    type Item = Result<(String, String), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pair = self.next_pair()?;
+
+        Some(pair.map(|(key, value, _)| (key, value)))
+    }
+}
@@ -0,0 +1,305 @@
+//!
+//! Expanding `${key}` references between values and to other sources
+//!
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::{on_line, Properties};
+
+/// A reason references could not be resolved
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// A value references a key that is not defined and has no default
+    #[error("\"{key}\"{} references undefined \"{reference}\"", on_line(.line))]
+    Undefined {
+        /// The key whose value has the reference
+        key: String,
+
+        /// The line that key was read from, if known
+        line: Option<usize>,
+
+        /// The key that is not defined
+        reference: String,
+    },
+
+    /// Keys reference each other, the path starts and ends with the same key
+    #[error("reference cycle {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+
+    /// A `${` is never closed
+    #[error("\"{key}\"{} has an unterminated reference", on_line(.line))]
+    Unterminated {
+        /// The key whose value has the reference
+        key: String,
+
+        /// The line that key was read from, if known
+        line: Option<usize>,
+    },
+}
+
+/// A source of values for `${prefix:name}` references, see [`Lookups`]
+pub trait Lookup {
+    /// The value for `name`, if there is one
+    fn lookup(&self, name: &str) -> Option<String>;
+}
+
+/// Looks names up as environment variables
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvLookup;
+
+impl Lookup for EnvLookup {
+    fn lookup(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+impl<S: std::hash::BuildHasher> Lookup for HashMap<String, String, S> {
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl<L: Lookup + ?Sized> Lookup for &L {
+    fn lookup(&self, name: &str) -> Option<String> {
+        (**self).lookup(name)
+    }
+}
+
+/// The lookups to use for references by their prefix
+///
+/// A prefix that has no lookup is read as part of `${key:default}`.
+#[derive(Default)]
+pub struct Lookups<'a> {
+    prefixes: HashMap<String, Box<dyn Lookup + 'a>>,
+}
+
+impl<'a> Lookups<'a> {
+    /// No lookups, so every reference is to another key
+    pub fn new() -> Self {
+        Lookups::default()
+    }
+
+    /// Resolves `${prefix:name}` and `${prefix:name:default}` through `lookup`
+    pub fn with(mut self, prefix: impl Into<String>, lookup: impl Lookup + 'a) -> Self {
+        self.prefixes.insert(prefix.into(), Box::new(lookup));
+        self
+    }
+
+    fn get(&self, prefix: &str) -> Option<&(dyn Lookup + 'a)> {
+        self.prefixes.get(prefix).map(Box::as_ref)
+    }
+}
+
+pub(crate) fn resolve(
+    props: &Properties,
+    lookups: &Lookups,
+) -> Result<Properties<'static>, ResolveError> {
+    let mut first = HashMap::with_capacity(props.pairs.len());
+
+    for (i, (k, _)) in props.pairs.iter().enumerate() {
+        first.entry(k.as_ref()).or_insert(i);
+    }
+
+    let mut resolver = Resolver {
+        props,
+        lookups,
+        first,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    };
+
+    let mut out = Properties {
+        encoding: props.encoding,
+        line_ending: props.line_ending,
+        index: props.index.as_ref().map(|_| OnceLock::new()),
+        ..Default::default()
+    };
+
+    for (i, (k, _)) in props.pairs.iter().enumerate() {
+        let value = if resolver.first.get(k.as_ref()) == Some(&i) {
+            resolver.key(k).map(Option::unwrap_or_default)?
+        } else {
+            resolver.stack.push(k);
+            let value = resolver.pair(i)?;
+            resolver.stack.pop();
+
+            value
+        };
+
+        out.push(Cow::Owned(k.to_string()), Cow::Owned(value), props.lines[i]);
+    }
+
+    Ok(out)
+}
+
+struct Resolver<'a> {
+    props: &'a Properties<'a>,
+    lookups: &'a Lookups<'a>,
+
+    /// Position of the pair used for each key
+    first: HashMap<&'a str, usize>,
+
+    /// Values already expanded
+    resolved: HashMap<&'a str, String>,
+
+    /// The keys being expanded, to find cycles
+    stack: Vec<&'a str>,
+}
+
+impl<'a> Resolver<'a> {
+    /// The expanded value of a key, if it is defined
+    fn key(&mut self, key: &str) -> Result<Option<String>, ResolveError> {
+        if let Some(value) = self.resolved.get(key) {
+            return Ok(Some(value.clone()));
+        }
+
+        let Some((key, pos)) = self.first.get_key_value(key).map(|(k, pos)| (*k, *pos)) else {
+            return Ok(None);
+        };
+
+        if let Some(start) = self.stack.iter().position(|k| *k == key) {
+            let mut cycle = self.stack[start..]
+                .iter()
+                .map(|k| k.to_string())
+                .collect::<Vec<_>>();
+
+            cycle.push(key.to_string());
+
+            return Err(ResolveError::Cycle(cycle));
+        }
+
+        self.stack.push(key);
+        let value = self.pair(pos)?;
+        self.stack.pop();
+
+        self.resolved.insert(key, value.clone());
+
+        Ok(Some(value))
+    }
+
+    /// Expands the value of the pair at `pos`
+    fn pair(&mut self, pos: usize) -> Result<String, ResolveError> {
+        let props = self.props;
+
+        self.text(&props.pairs[pos].1, pos)
+    }
+
+    /// Expands the references in `text`, which is from the value of the pair at `pos`
+    fn text(&mut self, text: &str, pos: usize) -> Result<String, ResolveError> {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(at) = rest.find('$') {
+            out.push_str(&rest[..at]);
+            rest = &rest[at..];
+
+            if let Some(escaped) = rest.strip_prefix("$${") {
+                out.push_str("${");
+                rest = escaped;
+                continue;
+            }
+
+            let Some(inner) = rest.strip_prefix("${") else {
+                out.push('$');
+                rest = &rest[1..];
+                continue;
+            };
+
+            let Some(close) = closing(inner) else {
+                let (key, _) = &self.props.pairs[pos];
+
+                return Err(ResolveError::Unterminated {
+                    key: key.to_string(),
+                    line: self.props.lines[pos],
+                });
+            };
+
+            let mut reference = &inner[..close];
+            let mut lookup = None;
+
+            if let Some(colon) = separator(reference) {
+                if let Some(found) = self.lookups.get(&reference[..colon]) {
+                    lookup = Some((&reference[..colon], found));
+                    reference = &reference[colon + 1..];
+                }
+            }
+
+            let (name, default) = match separator(reference) {
+                Some(colon) => (&reference[..colon], Some(&reference[colon + 1..])),
+                None => (reference, None),
+            };
+
+            let name = self.text(name, pos)?;
+
+            let value = match lookup {
+                Some((_, lookup)) => lookup.lookup(&name),
+                None => self.key(&name)?,
+            };
+
+            match (value, default) {
+                (Some(value), _) => out.push_str(&value),
+                (None, Some(default)) => {
+                    let default = self.text(default, pos)?;
+                    out.push_str(&default);
+                }
+                (None, None) => {
+                    let (key, _) = &self.props.pairs[pos];
+
+                    let reference = match lookup {
+                        Some((prefix, _)) => format!("{prefix}:{name}"),
+                        None => name,
+                    };
+
+                    return Err(ResolveError::Undefined {
+                        key: key.to_string(),
+                        line: self.props.lines[pos],
+                        reference,
+                    });
+                }
+            }
+
+            rest = &inner[close + 1..];
+        }
+
+        out.push_str(rest);
+
+        Ok(out)
+    }
+}
+
+/// Finds the `}` closing a reference, skipping over the ones nested in it
+fn closing(inner: &str) -> Option<usize> {
+    let mut depth = 0;
+    let bytes = inner.as_bytes();
+
+    for (i, ch) in bytes.iter().enumerate() {
+        match ch {
+            b'{' if i > 0 && bytes[i - 1] == b'$' => depth += 1,
+            b'}' if depth == 0 => return Some(i),
+            b'}' => depth -= 1,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Finds the `:` between a key and its default, ignoring ones in nested references
+fn separator(inner: &str) -> Option<usize> {
+    let mut depth = 0;
+    let bytes = inner.as_bytes();
+
+    for (i, ch) in bytes.iter().enumerate() {
+        match ch {
+            b'{' if i > 0 && bytes[i - 1] == b'$' => depth += 1,
+            b'}' => depth -= 1,
+            b':' if depth == 0 => return Some(i),
+            _ => (),
+        }
+    }
+
+    None
+}
@@ -0,0 +1,520 @@
+//!
+//! Serializing types into properties
+//!
+//! Nested structs and maps are flattened into `.` separated keys, sequences
+//! get their index as the last key segment, or an empty value when they are
+//! empty, and unit enum variants are written as their name.
+//!
+//! `None` is not written at all, and since loading reads an empty value as
+//! `None` too, `Some` of an empty string comes back as `None`.
+//!
+
+use std::fmt::Display;
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::{Error, Properties};
+
+/// Serializes a type into properties
+pub fn to_properties<T: Serialize + ?Sized>(value: &T) -> Result<Properties<'static>, Error> {
+    let mut props = Properties::default();
+
+    value.serialize(Serializer {
+        props: &mut props,
+        key: String::new(),
+    })?;
+
+    Ok(props)
+}
+
+/// Serializes a type into properties content
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    Ok(to_properties(value)?.to_string())
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Serde {
+            key: None,
+            line: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+fn unsupported(key: &str, what: &str) -> Error {
+    Error::Serde {
+        key: (!key.is_empty()).then(|| key.to_string()),
+        line: None,
+        message: format!("{what} can not be written as properties"),
+    }
+}
+
+/// Writes everything under `key`
+struct Serializer<'a> {
+    props: &'a mut Properties<'static>,
+    key: String,
+}
+
+impl<'a> Serializer<'a> {
+    fn child(&mut self, segment: impl Display) -> Serializer<'_> {
+        let key = if self.key.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{}", self.key, segment)
+        };
+
+        Serializer {
+            props: self.props,
+            key,
+        }
+    }
+
+    fn value(self, value: impl Display) -> Result<(), Error> {
+        if self.key.is_empty() {
+            return Err(Error::Serde {
+                key: None,
+                line: None,
+                message: "a value needs a key, only structs and maps can be written as properties"
+                    .to_string(),
+            });
+        }
+
+        self.props.insert(self.key, value.to_string());
+
+        Ok(())
+    }
+}
+
+macro_rules! display {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                self.value(v)
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    display! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        match std::str::from_utf8(v) {
+            Ok(s) => self.value(s),
+            Err(_) => Err(unsupported(&self.key, "bytes that are not utf-8")),
+        }
+    }
+
+    /// Nothing is written so loading gives back none
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.value("")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.value("")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.value(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        mut self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self.child(variant))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>, Error> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a>, Error> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        let key = self.child(variant).key;
+
+        Ok(Compound::new(Serializer {
+            props: self.props,
+            key,
+        }))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>, Error> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'a>, Error> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        self.serialize_tuple_variant(name, index, variant, len)
+    }
+}
+
+/// Writes the elements of sequences, maps and structs under their parent key
+struct Compound<'a> {
+    parent: Serializer<'a>,
+    index: usize,
+    key: Option<String>,
+}
+
+impl<'a> Compound<'a> {
+    fn new(parent: Serializer<'a>) -> Self {
+        Compound {
+            parent,
+            index: 0,
+            key: None,
+        }
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let index = self.index;
+        self.index += 1;
+
+        value.serialize(self.parent.child(index))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    /// An empty sequence is written as an empty value, so loading does not find it missing
+    fn end(self) -> Result<(), Error> {
+        if self.index == 0 && !self.parent.key.is_empty() {
+            self.parent.value("")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer(&self.parent.key))?);
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.key.take().expect("value serialized before key");
+
+        value.serialize(self.parent.child(key))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self.parent.child(key))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self.parent.child(key))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Turns map keys into a key segment, the parent key is only used for errors
+struct KeySerializer<'a>(&'a str);
+
+macro_rules! key {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<String, Error> {
+                Ok(v.to_string())
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for KeySerializer<'_> {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    key! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Keys with `.`, `[` or `]` in them would be read back as nested keys
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        if v.contains(['.', '[', ']']) {
+            return Err(unsupported(self.0, "a key with `.`, `[` or `]` in it"));
+        }
+
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, Error> {
+        Err(unsupported(self.0, "a float key"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, Error> {
+        Err(unsupported(self.0, "a float key"))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(unsupported(self.0, "a bytes key"))
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(unsupported(self.0, "an optional key"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, Error> {
+        Err(unsupported(self.0, "an optional key"))
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(unsupported(self.0, "a unit key"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(unsupported(self.0, "a unit key"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(unsupported(self.0, "an enum key with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(unsupported(self.0, "a sequence key"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(unsupported(self.0, "a tuple key"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(unsupported(self.0, "a tuple key"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(unsupported(self.0, "an enum key with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(unsupported(self.0, "a map key"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(unsupported(self.0, "a struct key"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(unsupported(self.0, "an enum key with data"))
+    }
+}
@@ -0,0 +1,271 @@
+//!
+//! Getting values as other types than strings
+//!
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{on_line, Properties};
+
+/// A value could not be read as the type asked for
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("\"{key}\"{} has invalid value \"{value}\": {message}", on_line(.line))]
+pub struct GetError {
+    /// The key that was asked for
+    pub key: String,
+
+    /// The value as it is in the properties
+    pub value: String,
+
+    /// The line the key was read from, if known
+    pub line: Option<usize>,
+
+    /// Why the value is invalid
+    pub message: String,
+}
+
+impl Properties<'_> {
+    /// Parses the value of the first matching key
+    pub fn get_as<T>(&self, key: &str) -> Result<Option<T>, GetError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get_with(key, |value| value.parse::<T>().map_err(|e| e.to_string()))
+    }
+
+    /// Reads `true`, `yes` or `on` as true and `false`, `no` or `off` as false, ignoring case
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, GetError> {
+        self.get_with(key, |value| match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" => Ok(true),
+            "false" | "no" | "off" => Ok(false),
+            _ => Err("expected true, false, yes, no, on or off".to_string()),
+        })
+    }
+
+    /// Reads a duration like `30s`, `1h30m` or `500ms`, or in ISO 8601 like `PT1H`
+    ///
+    /// The units are `ns`, `us`, `ms`, `s`, `m`, `h` and `d`.
+    pub fn get_duration(&self, key: &str) -> Result<Option<Duration>, GetError> {
+        self.get_with(key, duration)
+    }
+
+    /// Reads a size in bytes like `512`, `10MB` or `512KiB`, ignoring case
+    ///
+    /// `KB`, `MB`, `GB` and `TB` are powers of 1000 while `KiB`, `MiB`, `GiB` and `TiB` are powers of 1024.
+    pub fn get_size(&self, key: &str) -> Result<Option<u64>, GetError> {
+        self.get_with(key, size)
+    }
+
+    /// Splits the value on `delimiter` and parses every trimmed item, an empty value is an empty list
+    pub fn get_list<T>(&self, key: &str, delimiter: char) -> Result<Option<Vec<T>>, GetError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get_with(key, |value| {
+            if value.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            value
+                .split(delimiter)
+                .map(|item| {
+                    let item = item.trim();
+                    item.parse::<T>()
+                        .map_err(|e| format!("item \"{item}\" is invalid, {e}"))
+                })
+                .collect()
+        })
+    }
+
+    /// Converts the value of the first matching key, naming the key when it fails
+    fn get_with<T>(
+        &self,
+        key: &str,
+        convert: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, GetError> {
+        let Some(pos) = self.position(key) else {
+            return Ok(None);
+        };
+
+        let value = &self.pairs[pos].1;
+
+        match convert(value.trim()) {
+            Ok(v) => Ok(Some(v)),
+            Err(message) => Err(GetError {
+                key: key.to_string(),
+                value: value.to_string(),
+                line: self.lines[pos],
+                message,
+            }),
+        }
+    }
+}
+
+fn duration(value: &str) -> Result<Duration, String> {
+    const SECOND: u128 = 1_000_000_000;
+
+    let nanos = if let Some(iso) = value.strip_prefix(['P', 'p']) {
+        iso_duration(iso).ok_or("expected an ISO 8601 duration like PT1H30M")?
+    } else {
+        let mut nanos = 0;
+        let mut rest = value;
+
+        if rest.is_empty() {
+            return Err("expected a duration like 30s".to_string());
+        }
+
+        while !rest.is_empty() {
+            let (amount, len) = number(rest).ok_or("expected a duration like 30s")?;
+            rest = &rest[len..];
+
+            let unit_len = rest
+                .find(|ch: char| ch.is_ascii_digit())
+                .unwrap_or(rest.len());
+
+            let unit = match rest[..unit_len].trim() {
+                "ns" => 1,
+                "us" | "µs" => 1_000,
+                "ms" => 1_000_000,
+                "s" => SECOND,
+                "m" => 60 * SECOND,
+                "h" => 60 * 60 * SECOND,
+                "d" => 24 * 60 * 60 * SECOND,
+                "" => return Err("missing a unit, like s or ms".to_string()),
+                unit => return Err(format!("unknown unit \"{unit}\"")),
+            };
+
+            nanos = amount.times(unit).saturating_add(nanos);
+            rest = &rest[unit_len..];
+        }
+
+        nanos
+    };
+
+    let secs = u64::try_from(nanos / SECOND).map_err(|_| "the duration is too long")?;
+
+    Ok(Duration::new(secs, (nanos % SECOND) as u32))
+}
+
+/// The nanoseconds in the rest of an ISO 8601 duration after the `P`
+fn iso_duration(iso: &str) -> Option<u128> {
+    const SECOND: u128 = 1_000_000_000;
+
+    let (days, time) = match iso.find(['T', 't']) {
+        Some(t) => (&iso[..t], Some(&iso[t + 1..])),
+        None => (iso, None),
+    };
+
+    let mut nanos = 0;
+
+    if !days.is_empty() {
+        let (amount, len) = number(days)?;
+
+        if !days[len..].eq_ignore_ascii_case("d") || amount.fraction.1 > 0 {
+            return None;
+        }
+
+        nanos = amount.times(24 * 60 * 60 * SECOND);
+    }
+
+    let Some(mut rest) = time else {
+        return (!days.is_empty()).then_some(nanos);
+    };
+
+    // the units have to be in this order
+    let mut units = [('h', 60 * 60 * SECOND), ('m', 60 * SECOND), ('s', SECOND)].iter();
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let (amount, len) = number(rest)?;
+        let unit = rest[len..].chars().next()?.to_ascii_lowercase();
+
+        let (_, scale) = units.by_ref().find(|(u, _)| *u == unit)?;
+
+        if amount.fraction.1 > 0 && unit != 's' {
+            return None;
+        }
+
+        nanos = amount.times(*scale).saturating_add(nanos);
+        rest = &rest[len + 1..];
+    }
+
+    Some(nanos)
+}
+
+fn size(value: &str) -> Result<u64, String> {
+    let (amount, len) = number(value).ok_or("expected a size like 10MB")?;
+
+    let scale: u128 = match value[len..].trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1000,
+        "mb" => 1000_u128.pow(2),
+        "gb" => 1000_u128.pow(3),
+        "tb" => 1000_u128.pow(4),
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        _ => return Err(format!("unknown unit \"{}\"", value[len..].trim())),
+    };
+
+    u64::try_from(amount.times(scale)).map_err(|_| "the size is too large".to_string())
+}
+
+/// A decimal number, kept exact
+struct Amount {
+    whole: u128,
+
+    /// The digits after the point and how many there are
+    fraction: (u128, u32),
+}
+
+impl Amount {
+    /// Multiplies by `unit`, dropping what is left of the fraction
+    fn times(&self, unit: u128) -> u128 {
+        let (digits, len) = self.fraction;
+
+        self.whole
+            .saturating_mul(unit)
+            .saturating_add(digits * unit / 10_u128.pow(len))
+    }
+}
+
+/// Reads a number at the start, giving it and how long it was
+fn number(s: &str) -> Option<(Amount, usize)> {
+    let whole_len = s.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(s.len());
+    let whole = s[..whole_len].parse().ok()?;
+
+    let Some(after) = s[whole_len..].strip_prefix('.') else {
+        return Some((
+            Amount {
+                whole,
+                fraction: (0, 0),
+            },
+            whole_len,
+        ));
+    };
+
+    let fraction_len = after
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(after.len());
+
+    // anything past nanoseconds is dropped anyway
+    let kept = fraction_len.min(18);
+    let fraction = match kept {
+        0 => 0,
+        _ => after[..kept].parse().ok()?,
+    };
+
+    Some((
+        Amount {
+            whole,
+            fraction: (fraction, kept as u32),
+        },
+        whole_len + 1 + fraction_len,
+    ))
+}
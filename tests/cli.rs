@@ -0,0 +1,204 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const APP: &str = "# database\ndb.host=localhost\ndb.port = 5432\n\nname=app\\\n  server\nurl=${db.host}:${db.port}\n";
+
+/// Runs the binary with `stdin` as its input
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jprops"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+/// A file in the temp directory that is removed afterwards
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, content: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("jprops-{}-{name}", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+
+        TempFile(path)
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+
+    fn read(&self) -> String {
+        std::fs::read_to_string(&self.0).unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn get() {
+    let output = run(&["get", "name"], APP);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "appserver\n");
+
+    let output = run(&["get", "url", "--resolve"], APP);
+    assert_eq!(stdout(&output), "localhost:5432\n");
+
+    let output = run(&["get", "missing"], APP);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+
+    let output = run(&["get", "missing", "--default", "none"], APP);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "none\n");
+
+    let file = TempFile::new("get.properties", APP);
+    let output = run(&["get", "db.port", file.path()], "");
+    assert_eq!(stdout(&output), "5432\n");
+}
+
+#[test]
+fn set_and_delete() {
+    let output = run(&["set", "db.port", "6543"], APP);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), APP.replace("5432", "6543"));
+
+    let output = run(&["set", "new", "value"], APP);
+    assert_eq!(stdout(&output), format!("{APP}new=value\n"));
+
+    let output = run(&["set", "flag", "on"], "flag\nother=1\n");
+    assert_eq!(stdout(&output), "flag=on\nother=1\n");
+
+    let output = run(&["get", "flag"], stdout(&output));
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "on\n");
+
+    let output = run(&["delete", "db.host"], APP);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), APP.replace("db.host=localhost\n", ""));
+
+    let output = run(&["delete", "missing"], APP);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), APP);
+}
+
+#[test]
+fn in_place() {
+    let file = TempFile::new("in-place.properties", APP);
+
+    let output = run(&["set", "-i", "name", "other", file.path()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    assert!(file.read().starts_with("# database\n"));
+    assert!(file.read().contains("name=other\n"));
+
+    let output = run(&["delete", "--in-place", "url", file.path()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!file.read().contains("url"));
+
+    let output = run(&["set", "-i", "a", "b"], APP);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--in-place needs a file"));
+}
+
+#[test]
+fn list_and_keys() {
+    let output = run(&["list"], APP);
+    assert_eq!(
+        stdout(&output),
+        "db.host=localhost\ndb.port=5432\nname=appserver\nurl=${db.host}\\:${db.port}\n"
+    );
+
+    let output = run(&["list", "--prefix", "db"], APP);
+    assert_eq!(stdout(&output), "host=localhost\nport=5432\n");
+
+    let output = run(&["keys"], APP);
+    assert_eq!(stdout(&output), "db.host\ndb.port\nname\nurl\n");
+
+    let output = run(&["keys", "--prefix", "db"], APP);
+    assert_eq!(stdout(&output), "host\nport\n");
+}
+
+#[test]
+fn merge() {
+    let defaults = TempFile::new("defaults.properties", "host=localhost\nport=5432\n");
+    let overrides = TempFile::new("overrides.properties", "port=6543\nuser=admin\n");
+
+    let output = run(&["merge", defaults.path(), overrides.path()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "host=localhost\nport=6543\nuser=admin\n");
+
+    let output = run(&["merge"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn fmt() {
+    let output = run(
+        &["fmt", "--sort", "--separator", "spaced"],
+        "b:2\n# gone\na   1\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a = 1\nb = 2\n");
+
+    let output = run(&["fmt", "--ascii"], "name=café\r\n");
+    assert_eq!(stdout(&output), "name=caf\\u00E9\r\n");
+}
+
+#[test]
+fn check() {
+    let output = run(&["check"], APP);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+
+    let output = run(&["check"], "a=1\nb=\\u12\na=2\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("stdin: error: "));
+    assert!(stderr(&output).contains("--> 2:"));
+    assert!(stderr(&output).contains("stdin: \"a\" is defined on lines 1, 3\n"));
+
+    let output = run(&["check", "--allow-duplicates"], "a=1\na=2\n");
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run(&["check", "/nonexistent/jprops.properties"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn diff() {
+    let old = TempFile::new("old.properties", "a=1\nb=2\nc=3\n");
+    let new = TempFile::new("new.properties", "# comment\nc=3\nb=20\nd=4\n");
+
+    let output = run(&["diff", old.path(), new.path()], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "-a=1\n-b=2\n+b=20\n+d=4\n");
+
+    let output = run(&["diff", old.path(), old.path()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
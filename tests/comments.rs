@@ -0,0 +1,103 @@
+use jprops::Properties;
+
+#[test]
+fn hash_in_value() {
+    let content = b"url=http://host/#anchor\ngreeting=Hi!\ncolor = #ff0000";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(props.get("url"), Some("http://host/#anchor"));
+    assert_eq!(props.get("greeting"), Some("Hi!"));
+    assert_eq!(props.get("color"), Some("#ff0000"));
+}
+
+#[test]
+fn indented_comments() {
+    let content = b"   # indented\n\t! tabbed\nname=value";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 1);
+
+    assert_eq!(props.get("name"), Some("value"));
+}
+
+#[test]
+fn continuation_is_not_comment() {
+    let content = b"list=first,\\\n  #second,\\\n  !third\n# real comment";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 1);
+
+    assert_eq!(props.get("list"), Some("first,#second,!third"));
+}
+
+#[test]
+fn log4j() {
+    let content = b"# Root logger option\n\
+log4j.rootLogger=INFO, stdout\n\
+\n\
+# Direct log messages to stdout\n\
+log4j.appender.stdout=org.apache.log4j.ConsoleAppender\n\
+log4j.appender.stdout.Target=System.out\n\
+log4j.appender.stdout.layout=org.apache.log4j.PatternLayout\n\
+log4j.appender.stdout.layout.ConversionPattern=%d{yyyy-MM-dd HH:mm:ss} %-5p %c{1}:%L - %m%n\n";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 5);
+
+    assert_eq!(props.get("log4j.rootLogger"), Some("INFO, stdout"));
+    assert_eq!(
+        props.get("log4j.appender.stdout.layout.ConversionPattern"),
+        Some("%d{yyyy-MM-dd HH:mm:ss} %-5p %c{1}:%L - %m%n")
+    );
+}
+
+#[test]
+fn spring() {
+    let content = b"#\n\
+# Application settings\n\
+#\n\
+spring.datasource.url=jdbc:mysql://localhost:3306/db?useSSL=false#primary\n\
+spring.datasource.password=p@ss#w0rd!\n\
+  ! spring.profiles.active=dev\n\
+server.port = 8080\n";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(
+        props.get("spring.datasource.url"),
+        Some("jdbc:mysql://localhost:3306/db?useSSL=false#primary")
+    );
+    assert_eq!(props.get("spring.datasource.password"), Some("p@ss#w0rd!"));
+    assert_eq!(props.get("spring.profiles.active"), None);
+    assert_eq!(props.get("server.port"), Some("8080"));
+}
+
+#[test]
+fn messages() {
+    let content = b"# Messages for the greeting page\n\
+welcome.title=Welcome!\n\
+welcome.body=Hello {0}! You have {1} new message(s) #inbox\n\
+error.missing=Field ''{0}'' is required!\n";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(props.get("welcome.title"), Some("Welcome!"));
+    assert_eq!(
+        props.get("welcome.body"),
+        Some("Hello {0}! You have {1} new message(s) #inbox")
+    );
+    assert_eq!(
+        props.get("error.missing"),
+        Some("Field ''{0}'' is required!")
+    );
+}
@@ -0,0 +1,115 @@
+//!
+//! Runs every fixture in `tests/conformance` and compares the result with
+//! what `java.util.Properties.load` produced for it (see `Oracle.java`).
+//!
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use jprops::{DuplicatePolicy, Encoding, ParseOptions, Properties};
+
+/// Fixtures that are known to not match java along with the reason why
+const KNOWN: &[(&str, &str)] = &[("whitespace", "trailing whitespace is trimmed from values")];
+
+type Pairs = BTreeMap<String, String>;
+
+#[test]
+fn conformance() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance");
+
+    let mut fixtures = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "properties"))
+        .collect::<Vec<_>>();
+
+    fixtures.sort();
+
+    let mut divergences = Vec::new();
+
+    for path in &fixtures {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let known = KNOWN.iter().any(|(n, _)| *n == name);
+
+        let opts = ParseOptions {
+            encoding: if name.ends_with(".latin1") {
+                Encoding::Latin1
+            } else {
+                Encoding::Utf8
+            },
+            duplicates: DuplicatePolicy::Last,
+            ..Default::default()
+        };
+
+        let content = fs::read(path).unwrap();
+        let expected = expected(&fs::read_to_string(path.with_extension("expected")).unwrap());
+        let actual = Properties::load_with(&content, &opts).ok().map(|props| {
+            assert!(props.duplicates().is_empty(), "{name}: kept duplicates");
+
+            props
+                .key_values()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Pairs>()
+        });
+
+        if actual != expected && !known {
+            divergences.push(format!("{name}: expected {expected:?} but got {actual:?}"));
+        }
+
+        if actual == expected && known {
+            divergences.push(format!("{name}: conforms but is listed as known"));
+        }
+    }
+
+    for (name, _) in KNOWN {
+        if !fixtures
+            .iter()
+            .any(|path| path.file_stem().unwrap() == *name)
+        {
+            divergences.push(format!("{name}: is listed as known but does not exist"));
+        }
+    }
+
+    assert!(divergences.is_empty(), "\n{}\n", divergences.join("\n"));
+}
+
+/// Reads the oracle output, `None` meaning java failed to load the fixture
+fn expected(content: &str) -> Option<Pairs> {
+    if content == "ERROR\n" {
+        return None;
+    }
+
+    let pairs = content
+        .lines()
+        .map(|line| {
+            let (k, v) = line.split_once('\t').unwrap();
+            (unescape(k), unescape(v))
+        })
+        .collect();
+
+    Some(pairs)
+}
+
+fn unescape(s: &str) -> String {
+    let mut units = Vec::new();
+    let mut chars = s.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            units.push(ch as u16);
+            continue;
+        }
+
+        match chars.next() {
+            Some('\\') => units.push(b'\\' as u16),
+            Some('u') => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+                units.push(u16::from_str_radix(&hex, 16).unwrap());
+            }
+            other => panic!("unexpected escape {other:?} in oracle output"),
+        }
+    }
+
+    String::from_utf16(&units).unwrap()
+}
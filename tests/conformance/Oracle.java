@@ -0,0 +1,67 @@
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.Reader;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.Properties;
+import java.util.TreeSet;
+
+/**
+ * Regenerates the `.expected` file for each `.properties` fixture given.
+ *
+ * Files ending in `.latin1.properties` are loaded as ISO-8859-1 through an
+ * InputStream, everything else as UTF-8 through a Reader.
+ *
+ *     java tests/conformance/Oracle.java tests/conformance/*.properties
+ *
+ * Each line of the output is an escaped key and value separated by a tab, a
+ * file that fails to load holds a single `ERROR` line instead.
+ */
+public class Oracle {
+    public static void main(String[] args) throws IOException {
+        for (String arg : args) {
+            Path path = Paths.get(arg);
+            Properties props = new Properties();
+            StringBuilder out = new StringBuilder();
+
+            try {
+                if (arg.endsWith(".latin1.properties")) {
+                    try (InputStream in = Files.newInputStream(path)) {
+                        props.load(in);
+                    }
+                } else {
+                    try (Reader in = Files.newBufferedReader(path, StandardCharsets.UTF_8)) {
+                        props.load(in);
+                    }
+                }
+
+                for (String key : new TreeSet<>(props.stringPropertyNames())) {
+                    out.append(escape(key)).append('\t').append(escape(props.getProperty(key))).append('\n');
+                }
+            } catch (IllegalArgumentException e) {
+                out.append("ERROR\n");
+            }
+
+            Path expected = Paths.get(arg.replaceAll("\\.properties$", ".expected"));
+            Files.write(expected, out.toString().getBytes(StandardCharsets.US_ASCII));
+        }
+    }
+
+    static String escape(String s) {
+        StringBuilder out = new StringBuilder();
+
+        for (char ch : s.toCharArray()) {
+            if (ch == '\\') {
+                out.append("\\\\");
+            } else if (ch < 0x20 || ch > 0x7e) {
+                out.append(String.format("\\u%04X", (int) ch));
+            } else {
+                out.append(ch);
+            }
+        }
+
+        return out.toString();
+    }
+}
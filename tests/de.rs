@@ -0,0 +1,161 @@
+#![cfg(feature = "serde")]
+
+use jprops::{from_properties, from_slice, Error, Properties};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Config {
+    name: String,
+    debug: bool,
+    db: Db,
+    servers: Vec<String>,
+    timeout: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Db {
+    url: String,
+    pool: Pool,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Pool {
+    size: u16,
+    ratio: f64,
+}
+
+#[test]
+fn nested() {
+    let content = b"name=app\n\
+debug = true\n\
+db.url=jdbc:postgres://localhost\n\
+db.pool.size = 10\n\
+db.pool.ratio=0.5\n\
+servers.1=beta\n\
+servers.0=alpha\n";
+
+    let config: Config = from_slice(content).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            name: "app".to_string(),
+            debug: true,
+            db: Db {
+                url: "jdbc:postgres://localhost".to_string(),
+                pool: Pool {
+                    size: 10,
+                    ratio: 0.5
+                },
+            },
+            servers: vec!["alpha".to_string(), "beta".to_string()],
+            timeout: None,
+        }
+    );
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Lists {
+    bracketed: Vec<u8>,
+    repeated: Vec<String>,
+    single: Vec<String>,
+}
+
+#[test]
+fn sequences() {
+    let content =
+        b"bracketed[1]=2\nbracketed[0]=1\nbracketed[10]=3\nrepeated=a\nrepeated=b\nsingle=only";
+
+    let lists: Lists = from_slice(content).unwrap();
+
+    assert_eq!(
+        lists,
+        Lists {
+            bracketed: vec![1, 2, 3],
+            repeated: vec!["a".to_string(), "b".to_string()],
+            single: vec!["only".to_string()],
+        }
+    );
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Storage {
+    Disk { path: String },
+    Memory,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Logging {
+    level: Level,
+    storage: Storage,
+    other: Storage,
+    retries: Option<u8>,
+    empty: Option<u8>,
+}
+
+#[test]
+fn enums_and_options() {
+    let content = b"level=info\nstorage.Disk.path=/var/log\nother=Memory\nretries=3\nempty=";
+
+    let logging: Logging = from_slice(content).unwrap();
+
+    assert_eq!(
+        logging,
+        Logging {
+            level: Level::Info,
+            storage: Storage::Disk {
+                path: "/var/log".to_string()
+            },
+            other: Storage::Memory,
+            retries: Some(3),
+            empty: None,
+        }
+    );
+}
+
+#[test]
+fn map() {
+    let mut props = Properties::default();
+    props.insert_str("a", "1");
+    props.insert_str("b", "2");
+
+    let map: HashMap<&str, u32> = from_properties(&props).unwrap();
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["a"], 1);
+    assert_eq!(map["b"], 2);
+}
+
+#[test]
+fn parse_error() {
+    let content =
+        b"name=app\ndebug=true\nservers=a\n\ndb.url=x\ndb.pool.size=lots\ndb.pool.ratio=1";
+
+    let err = from_slice::<Config>(content).unwrap_err();
+
+    match err {
+        Error::Serde { key, line, .. } => {
+            assert_eq!(key.as_deref(), Some("db.pool.size"));
+            assert_eq!(line, Some(6));
+        }
+
+        e => panic!("unexpected error {e}"),
+    }
+}
+
+#[test]
+fn missing_field() {
+    let content = b"name=app\ndebug=true\nservers=a\ndb.url=x\ndb.pool.size=1";
+
+    let err = from_slice::<Config>(content).unwrap_err();
+
+    assert_eq!(err.to_string(), "key \"db.pool\": missing field `ratio`");
+}
@@ -0,0 +1,115 @@
+use jprops::{Encoding, Error, EscapeMode, ParseOptions, Properties, Span};
+use std::io::Cursor;
+
+fn strict() -> ParseOptions {
+    ParseOptions {
+        escapes: EscapeMode::Strict,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn escape_span() {
+    let content = b"first=1\r\n  name = a\\qb";
+
+    let err = Properties::load_with(content, &strict()).unwrap_err();
+
+    assert!(matches!(&err, Error::InvalidEscape(_, seq) if seq == "\\q"));
+    assert_eq!(
+        err.span(),
+        Some(&Span {
+            offset: 19,
+            len: 2,
+            line: 2,
+            column: 11,
+        })
+    );
+    assert_eq!(&content[err.span().unwrap().range()], b"\\q");
+}
+
+#[test]
+fn continued_span() {
+    let content = "a=caf\u{e9}\\\n  \u{e9}\\u12\n".as_bytes();
+
+    let err = Properties::load(content).unwrap_err();
+
+    assert_eq!(
+        err.span(),
+        Some(&Span {
+            offset: 13,
+            len: 4,
+            line: 2,
+            column: 4,
+        })
+    );
+    assert_eq!(&content[err.span().unwrap().range()], b"\\u12");
+}
+
+#[test]
+fn latin1_span() {
+    let content = b"caf\xe9=\xe9\\uD800";
+
+    let opts = ParseOptions {
+        encoding: Encoding::Latin1,
+        ..Default::default()
+    };
+
+    let err = Properties::load_with(content, &opts).unwrap_err();
+
+    assert_eq!(
+        err.span(),
+        Some(&Span {
+            offset: 6,
+            len: 6,
+            line: 1,
+            column: 7,
+        })
+    );
+}
+
+#[test]
+fn reader_span() {
+    let content = b"a=1\r\n\r\nb=\\\n  x\\uD83D";
+
+    let loaded = Properties::load(content).unwrap_err();
+    let read = Properties::from_reader(Cursor::new(content)).unwrap_err();
+
+    assert_eq!(loaded.span(), read.span());
+    assert_eq!(read.span().unwrap().offset, 14);
+}
+
+#[test]
+fn render() {
+    let content = b"first=1\n\tname=a\\qb\nlast=2";
+
+    let err = Properties::load_with(content, &strict()).unwrap_err();
+
+    assert_eq!(
+        err.render(content),
+        "error: line 2 has invalid escape sequence \"\\q\"\n \
+         --> 2:8\n  \
+         |\n\
+         2 | \tname=a\\qb\n  \
+         | \t      ^^\n"
+    );
+
+    let err = Error::Io(std::io::ErrorKind::UnexpectedEof.into());
+    assert_eq!(
+        err.render(content),
+        "error: failed to read properties: unexpected end of file\n"
+    );
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn diagnostic() {
+    use miette::Diagnostic;
+
+    let err = Properties::load(b"a=\\u00").unwrap_err();
+
+    let labels = err.labels().unwrap().collect::<Vec<_>>();
+
+    assert_eq!(err.code().unwrap().to_string(), "jprops::invalid_escape");
+    assert_eq!(labels.len(), 1);
+    assert_eq!((labels[0].offset(), labels[0].len()), (2, 4));
+}
@@ -0,0 +1,170 @@
+use jprops::document::PropertiesDocument;
+use jprops::Properties;
+
+const CONTENT: &[u8] = b"# database settings\n\
+db.url = jdbc:postgres://localhost\n\
+\n\
+! credentials\n\
+db.user:admin\n\
+targetCities=\\\n    Detroit,\\\n    Chicago\n\
+last=value";
+
+#[test]
+fn lossless() {
+    let doc = PropertiesDocument::parse(CONTENT).unwrap();
+
+    assert_eq!(doc.to_string().as_bytes(), CONTENT);
+}
+
+#[test]
+fn entries() {
+    let doc = PropertiesDocument::parse(CONTENT).unwrap();
+
+    assert_eq!(doc.len(), 4);
+    assert_eq!(doc.get("db.url"), Some("jdbc:postgres://localhost"));
+    assert_eq!(doc.get("targetCities"), Some("Detroit,Chicago"));
+
+    let lines = doc.entries().map(|e| e.line()).collect::<Vec<_>>();
+    assert_eq!(lines, vec![Some(2), Some(5), Some(6), Some(9)]);
+}
+
+#[test]
+fn set_in_place() {
+    let mut doc = PropertiesDocument::parse(CONTENT).unwrap();
+
+    doc.set("db.url", "jdbc:mysql://remote");
+    doc.set("db.user", "root");
+
+    assert_eq!(
+        doc.to_string(),
+        "# database settings\n\
+         db.url = jdbc\\:mysql\\://remote\n\
+         \n\
+         ! credentials\n\
+         db.user:root\n\
+         targetCities=\\\n    Detroit,\\\n    Chicago\n\
+         last=value"
+    );
+    assert_eq!(doc.get("db.url"), Some("jdbc:mysql://remote"));
+}
+
+#[test]
+fn set_continued() {
+    let mut doc = PropertiesDocument::parse(CONTENT).unwrap();
+
+    doc.set("targetCities", "Los Angeles");
+
+    assert_eq!(
+        doc.to_string(),
+        "# database settings\n\
+         db.url = jdbc:postgres://localhost\n\
+         \n\
+         ! credentials\n\
+         db.user:admin\n\
+         targetCities=Los Angeles\n\
+         last=value"
+    );
+}
+
+#[test]
+fn set_continued_key() {
+    let mut doc = PropertiesDocument::parse(b"db.\\\n  url = old # kept\\\n\n").unwrap();
+
+    assert_eq!(doc.get("db.url"), Some("old # kept"));
+
+    doc.set("db.url", "new");
+
+    assert_eq!(doc.to_string(), "db.\\\n  url = new\n");
+}
+
+#[test]
+fn set_key_only() {
+    let mut doc = PropertiesDocument::parse(b"flag\nspaced \\\n\nlast=value\n").unwrap();
+
+    assert_eq!(doc.get("flag"), Some(""));
+
+    doc.set("flag", "on");
+    doc.set("spaced", "yes");
+
+    assert_eq!(doc.to_string(), "flag=on\nspaced yes\nlast=value\n");
+
+    let doc = PropertiesDocument::parse(doc.to_string().as_bytes()).unwrap();
+    assert_eq!(doc.get("flag"), Some("on"));
+    assert_eq!(doc.get("spaced"), Some("yes"));
+    assert_eq!(doc.len(), 3);
+}
+
+#[test]
+fn push_after_continuation() {
+    for (content, expected) in [
+        (&b"a=b\\"[..], "a=b\nk=v\n"),
+        (b"a=b\\\n", "a=b\nk=v\n"),
+        (b"a=b\\\r\n  c\\", "a=b\\\r\n  c\r\nk=v\r\n"),
+    ] {
+        let mut doc = PropertiesDocument::parse(content).unwrap();
+
+        doc.push("k", "v");
+
+        let out = doc.to_string();
+        assert_eq!(out, expected);
+
+        let loaded = Properties::load(out.as_bytes()).unwrap();
+        assert_eq!(
+            loaded.key_values().collect::<Vec<_>>(),
+            vec![("a", doc.get("a").unwrap()), ("k", "v")]
+        );
+    }
+}
+
+#[test]
+fn set_appends() {
+    let mut doc = PropertiesDocument::parse(CONTENT).unwrap();
+
+    doc.set("new key", " spaced");
+
+    assert!(doc
+        .to_string()
+        .ends_with("last=value\nnew\\ key=\\ spaced\n"));
+    assert_eq!(doc.len(), 5);
+    assert_eq!(doc.get("new key"), Some(" spaced"));
+}
+
+#[test]
+fn delete() {
+    let mut doc = PropertiesDocument::parse(CONTENT).unwrap();
+
+    doc.delete("targetCities");
+    doc.delete("db.url");
+
+    assert_eq!(
+        doc.to_string(),
+        "# database settings\n\
+         \n\
+         ! credentials\n\
+         db.user:admin\n\
+         last=value"
+    );
+}
+
+#[test]
+fn to_properties() {
+    let doc = PropertiesDocument::parse(CONTENT).unwrap();
+    let props = doc.to_properties();
+
+    assert_eq!(props.len(), 4);
+    assert_eq!(props.get("last"), Some("value"));
+}
+
+#[test]
+fn crlf() {
+    let mut doc = PropertiesDocument::parse(b"# comment\r\na=1\r\nb=2").unwrap();
+
+    doc.set("a", "one");
+    doc.push("c", "3");
+
+    assert_eq!(doc.to_string(), "# comment\r\na=one\r\nb=2\r\nc=3\r\n");
+    assert_eq!(
+        doc.entries().map(|e| e.line()).collect::<Vec<_>>(),
+        vec![Some(2), Some(3), None]
+    );
+}
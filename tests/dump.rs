@@ -1,4 +1,6 @@
 use jprops::Properties;
+use proptest::collection::vec;
+use proptest::prelude::*;
 
 #[test]
 fn single_dump() {
@@ -31,3 +33,65 @@ fn duplicate_dump() {
         "hallo=mutter\nhallo=vater\nhallo=welt\nhello=world\n"
     );
 }
+
+#[test]
+fn escaped_dump() {
+    let mut props = Properties::default();
+
+    props.insert_str("my key", "a=b:c");
+    props.insert_str("#note", " padded ");
+    props.insert_str("path", "C:\\temp\\new\tline\n");
+    props.insert_str("name", "\u{e9}t\u{e9} \u{2603}");
+
+    assert_eq!(
+        props.to_string(),
+        "my\\ key=a\\=b\\:c\n\
+         \\#note=\\ padded\\ \n\
+         path=C\\:\\\\temp\\\\new\\tline\\n\n\
+         name=\u{e9}t\u{e9} \\u2603\n"
+    );
+}
+
+#[test]
+fn escaped_round_trip() {
+    let mut props = Properties::default();
+
+    props.insert_str("my key", "a=b:c");
+    props.insert_str("#note", " padded ");
+    props.insert_str("!bang", "!");
+    props.insert_str("", "");
+    props.insert_str("path", "C:\\temp\\new\tline\n");
+    props.insert_str("name", "\u{e9}t\u{e9} \u{2603}");
+
+    let dumped = props.to_string();
+    let loaded = Properties::load(dumped.as_bytes()).unwrap();
+
+    assert_eq!(
+        loaded.key_values().collect::<Vec<_>>(),
+        props.key_values().collect::<Vec<_>>()
+    );
+}
+
+proptest! {
+    #[test]
+    fn round_trip(pairs in vec((any::<String>(), any::<String>()), 0..16)) {
+        let props = Properties::from_iter(pairs);
+
+        let dumped = props.to_string();
+        let loaded = Properties::load(dumped.as_bytes()).unwrap();
+
+        prop_assert_eq!(
+            loaded.key_values().collect::<Vec<_>>(),
+            props.key_values().collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn surrogate_dump() {
+    let mut props = Properties::default();
+
+    props.insert_str("smile", "\u{1f600}");
+
+    assert_eq!(props.to_string(), "smile=\\uD83D\\uDE00\n");
+}
@@ -0,0 +1,65 @@
+use jprops::{Encoding, Error, ParseOptions, Properties, Span};
+
+const LATIN1: &[u8] = b"caf\xe9=cr\xe8me br\xfbl\xe9e\nplain=value\nescaped=\\u00e9\\u2603";
+const UTF8: &[u8] = "caf\u{e9}=cr\u{e8}me br\u{fb}l\u{e9}e\nplain=value".as_bytes();
+
+fn opts(encoding: Encoding) -> ParseOptions {
+    ParseOptions {
+        encoding,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn utf8_default() {
+    let props = Properties::load(UTF8).unwrap();
+
+    assert_eq!(props.get("caf\u{e9}"), Some("cr\u{e8}me br\u{fb}l\u{e9}e"));
+    assert_eq!(props.encoding(), Some(Encoding::Utf8));
+
+    assert!(matches!(
+        Properties::load(LATIN1),
+        Err(Error::InvalidUtf8(Span { line: 1, .. }, _))
+    ));
+}
+
+#[test]
+fn latin1() {
+    let props = Properties::load_with(LATIN1, &opts(Encoding::Latin1)).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(props.get("caf\u{e9}"), Some("cr\u{e8}me br\u{fb}l\u{e9}e"));
+    assert_eq!(props.get("plain"), Some("value"));
+    assert_eq!(props.get("escaped"), Some("\u{e9}\u{2603}"));
+    assert_eq!(props.encoding(), Some(Encoding::Latin1));
+}
+
+#[test]
+fn auto() {
+    let props = Properties::load_with(UTF8, &opts(Encoding::Auto)).unwrap();
+
+    assert_eq!(props.get("caf\u{e9}"), Some("cr\u{e8}me br\u{fb}l\u{e9}e"));
+    assert_eq!(props.encoding(), Some(Encoding::Utf8));
+
+    let props = Properties::load_with(LATIN1, &opts(Encoding::Auto)).unwrap();
+
+    assert_eq!(props.get("caf\u{e9}"), Some("cr\u{e8}me br\u{fb}l\u{e9}e"));
+    assert_eq!(props.encoding(), Some(Encoding::Latin1));
+}
+
+#[test]
+fn auto_checks_comments() {
+    let content = b"# r\xe9sum\xe9\nkey=value";
+
+    let props = Properties::load_with(content, &opts(Encoding::Auto)).unwrap();
+
+    assert_eq!(props.encoding(), Some(Encoding::Latin1));
+}
+
+#[test]
+fn constructed() {
+    let props = Properties::default();
+
+    assert_eq!(props.encoding(), None);
+}
@@ -0,0 +1,83 @@
+use jprops::layered::{Found, Layered};
+use jprops::Properties;
+
+fn layered() -> Layered<'static> {
+    let defaults = Properties::load(b"host=localhost\nport=5432\npool=10").unwrap();
+    let file =
+        Properties::load(b"# app.properties\nhost=db.internal\n\nport=6543\nport=7000").unwrap();
+
+    let mut overrides = Properties::default();
+    overrides.insert_str("port", "9999");
+
+    Layered::new()
+        .with("defaults", defaults)
+        .with("app.properties", file)
+        .with("overrides", overrides)
+}
+
+#[test]
+fn precedence() {
+    let layered = layered();
+
+    assert_eq!(
+        layered.get("host"),
+        Some(Found {
+            value: "db.internal",
+            layer: "app.properties",
+            line: Some(2),
+        })
+    );
+    assert_eq!(
+        layered.get("pool"),
+        Some(Found {
+            value: "10",
+            layer: "defaults",
+            line: Some(3),
+        })
+    );
+    assert_eq!(layered.get("port").unwrap().layer, "overrides");
+    assert_eq!(layered.get("port").unwrap().line, None);
+    assert_eq!(layered.get("missing"), None);
+
+    assert_eq!(
+        layered.layers().collect::<Vec<_>>(),
+        vec!["defaults", "app.properties", "overrides"]
+    );
+}
+
+#[test]
+fn shadowed() {
+    let layered = layered();
+
+    let shadowed = layered
+        .shadowed("port")
+        .into_iter()
+        .map(|found| (found.value, found.layer, found.line))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        shadowed,
+        vec![
+            ("6543", "app.properties", Some(4)),
+            ("7000", "app.properties", Some(5)),
+            ("5432", "defaults", Some(2)),
+        ]
+    );
+    assert!(layered.shadowed("pool").is_empty());
+}
+
+#[test]
+fn flatten() {
+    let layered = layered();
+    let props = layered.to_properties();
+
+    assert_eq!(
+        props.key_values().collect::<Vec<_>>(),
+        vec![("host", "db.internal"), ("port", "9999"), ("pool", "10")]
+    );
+    assert_eq!(props.line("host"), Some(2));
+    assert_eq!(
+        layered.layer("defaults").unwrap().get("host"),
+        Some("localhost")
+    );
+}
@@ -1,4 +1,6 @@
-use jprops::Properties;
+use jprops::{
+    Duplicate, DuplicatePolicy, Error, EscapeMode, LineEnding, ParseOptions, Properties, Span,
+};
 
 #[test]
 pub fn basic_eq_assignment() {
@@ -81,6 +83,20 @@ pub fn backspace_continue() {
     );
 }
 
+#[test]
+pub fn continued_escapes() {
+    let content = b"a=x\\\n  \\u0041\\tb\nsplit.\\\n  key = \\\n  # not a comment\neof=last\\";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(props.get("a"), Some("xA\tb"));
+    assert_eq!(props.get("split.key"), Some("# not a comment"));
+    assert_eq!(props.get("eof"), Some("last"));
+    assert_eq!(props.line("eof"), Some(6));
+}
+
 #[test]
 pub fn escaping() {
     let content = b"name=hello\\tworld\\r\\nhow are you?";
@@ -102,3 +118,292 @@ pub fn unicode() {
 
     assert_eq!(props.get("name"), Some("hello, world"));
 }
+
+#[test]
+pub fn whitespace_separator() {
+    let content = b"name value\nother \t  : spaced\nthird\t=  = equals";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(props.get("name"), Some("value"));
+    assert_eq!(props.get("other"), Some("spaced"));
+    assert_eq!(props.get("third"), Some("= equals"));
+}
+
+#[test]
+pub fn key_only() {
+    let content = b"flag\nother=value\n  spaced   \n";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(props.get("flag"), Some(""));
+    assert_eq!(props.get("other"), Some("value"));
+    assert_eq!(props.get("spaced"), Some(""));
+}
+
+#[test]
+pub fn escaped_key() {
+    let content = b"a\\=b=c\nmy\\ key=value\nhost\\:port : 8080\nx\\\\=y";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 4);
+
+    assert_eq!(props.get("a=b"), Some("c"));
+    assert_eq!(props.get("my key"), Some("value"));
+    assert_eq!(props.get("host:port"), Some("8080"));
+    assert_eq!(props.get("x\\"), Some("y"));
+}
+
+#[test]
+pub fn surrogate_pair() {
+    let content = b"smile=\\uD83D\\uDE00\nmixed=a\\ud83d\\ude00b";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.get("smile"), Some("\u{1f600}"));
+    assert_eq!(props.get("mixed"), Some("a\u{1f600}b"));
+}
+
+#[test]
+pub fn unpaired_surrogate() {
+    let res = Properties::load(b"first=1\nhigh=ab\\uD83Dcd");
+    assert!(matches!(
+        res,
+        Err(Error::UnpairedSurrogate(
+            Span {
+                line: 2,
+                column: 8,
+                ..
+            },
+            0xD83D
+        ))
+    ));
+
+    let res = Properties::load(b"low = \\uDE00");
+    assert!(matches!(
+        res,
+        Err(Error::UnpairedSurrogate(
+            Span {
+                line: 1,
+                column: 7,
+                ..
+            },
+            0xDE00
+        ))
+    ));
+
+    let res = Properties::load(b"twice=\\uD83D\\uD83D");
+    assert!(matches!(
+        res,
+        Err(Error::UnpairedSurrogate(
+            Span {
+                line: 1,
+                column: 7,
+                ..
+            },
+            0xD83D
+        ))
+    ));
+
+    let res = Properties::load(b"k\\uD83D=v");
+    assert!(matches!(
+        res,
+        Err(Error::UnpairedSurrogate(
+            Span {
+                line: 1,
+                column: 2,
+                ..
+            },
+            0xD83D
+        ))
+    ));
+}
+
+#[test]
+pub fn unknown_escapes() {
+    let content = b"quote=\\\"quoted\\\"\nletters=\\q\\z\nkey\\qed=value";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.get("quote"), Some("\"quoted\""));
+    assert_eq!(props.get("letters"), Some("qz"));
+    assert_eq!(props.get("keyqed"), Some("value"));
+}
+
+#[test]
+pub fn strict_escapes() {
+    let opts = ParseOptions {
+        escapes: EscapeMode::Strict,
+        ..Default::default()
+    };
+
+    let res = Properties::load_with(b"first=1\nletters=\\q", &opts);
+    assert!(matches!(
+        res,
+        Err(Error::InvalidEscape(Span { line: 2, .. }, _))
+    ));
+
+    let props = Properties::load_with(b"known=\\t\\=\\:\\#\\!\\ \\\\\\u0041", &opts).unwrap();
+    assert_eq!(props.get("known"), Some("\t=:#! \\A"));
+}
+
+#[test]
+pub fn java_store() {
+    // written by java.util.Properties.store
+    let content = b"#comment\n#Sat Oct 18 12\\:00\\:00 UTC 2026\n\
+key\\ with\\ spaces=\\ leading and trailing \n\
+url=http\\://host\\:8080/path\\#anchor\n\
+unicode=caf\\u00E9 \\u2603\n";
+
+    let props = Properties::load(content).unwrap();
+
+    assert_eq!(props.len(), 3);
+
+    assert_eq!(props.get("key with spaces"), Some(" leading and trailing"));
+    assert_eq!(props.get("url"), Some("http://host:8080/path#anchor"));
+    assert_eq!(props.get("unicode"), Some("caf\u{e9} \u{2603}"));
+}
+
+#[test]
+pub fn line_endings() {
+    let props = Properties::load(b"# comment\r\n\r\na=1\r\nb=\\\r\n  2\r\nc=3").unwrap();
+
+    assert_eq!(props.get("b"), Some("2"));
+    assert_eq!(props.line("a"), Some(3));
+    assert_eq!(props.line("c"), Some(6));
+    assert_eq!(props.line_ending(), Some(LineEnding::Crlf));
+
+    let props = Properties::load(b"a=1\rb=2\r").unwrap();
+
+    assert_eq!(props.line("b"), Some(2));
+    assert_eq!(props.line_ending(), Some(LineEnding::Cr));
+    assert_eq!(props.to_string(), "a=1\rb=2\r");
+
+    let res = Properties::load_with(
+        b"a=1\r\nb=2\r\nc=\\q",
+        &ParseOptions {
+            escapes: EscapeMode::Strict,
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        res,
+        Err(Error::InvalidEscape(Span { line: 3, .. }, _))
+    ));
+
+    assert_eq!(Properties::load(b"a=1").unwrap().line_ending(), None);
+}
+
+#[test]
+pub fn lenient() {
+    let content = b"a=1\nb=\\u12\nc=\xff\nd=\\\n  \\uD800\ne=5\nf=\\u00";
+
+    let (props, errors) = Properties::load_lenient(content);
+
+    assert_eq!(
+        props.key_values().collect::<Vec<_>>(),
+        vec![("a", "1"), ("e", "5")]
+    );
+    assert_eq!(props.line("e"), Some(6));
+
+    let lines = errors
+        .iter()
+        .map(|e| e.span().unwrap().line)
+        .collect::<Vec<_>>();
+    assert_eq!(lines, vec![2, 3, 5, 7]);
+
+    assert!(matches!(errors[1], Error::InvalidUtf8(..)));
+    assert!(matches!(errors[2], Error::UnpairedSurrogate(..)));
+
+    let (props, errors) = Properties::load_lenient_with(
+        b"a=\\q\nb=2",
+        &ParseOptions {
+            escapes: EscapeMode::Strict,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(props.len(), 1);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+pub fn duplicate_policy() {
+    let content = b"a=1\nb=2\n\na=3\nb=4\na=5";
+
+    let load = |duplicates| {
+        Properties::load_with(
+            content,
+            &ParseOptions {
+                duplicates,
+                ..Default::default()
+            },
+        )
+    };
+
+    let props = load(DuplicatePolicy::KeepAll).unwrap();
+    assert_eq!(props.len(), 5);
+
+    let props = load(DuplicatePolicy::First).unwrap();
+    assert_eq!(
+        props.key_values().collect::<Vec<_>>(),
+        vec![("a", "1"), ("b", "2")]
+    );
+    assert_eq!(props.line("a"), Some(1));
+
+    let props = load(DuplicatePolicy::Last).unwrap();
+    assert_eq!(
+        props.key_values().collect::<Vec<_>>(),
+        vec![("a", "5"), ("b", "4")]
+    );
+    assert_eq!(props.line("a"), Some(6));
+
+    let err = load(DuplicatePolicy::Error).unwrap_err();
+    assert!(
+        matches!(&err, Error::DuplicateKey(Span { line: 4, column: 1, len: 1, .. }, key, 1) if key == "a")
+    );
+    assert_eq!(
+        err.to_string(),
+        "line 4 defines \"a\" again, it was first defined on line 1"
+    );
+
+    let (props, errors) = Properties::load_lenient_with(
+        content,
+        &ParseOptions {
+            duplicates: DuplicatePolicy::Error,
+            ..Default::default()
+        },
+    );
+    assert_eq!(props.len(), 2);
+    assert_eq!(errors.len(), 3);
+}
+
+#[test]
+pub fn duplicates() {
+    let props = Properties::load(b"a=1\nb=2\nc=3\nb=4\na=5\nb=6").unwrap();
+
+    assert_eq!(
+        props.duplicates(),
+        vec![
+            Duplicate {
+                key: "a",
+                values: vec!["1", "5"],
+                lines: vec![Some(1), Some(5)],
+            },
+            Duplicate {
+                key: "b",
+                values: vec!["2", "4", "6"],
+                lines: vec![Some(2), Some(4), Some(6)],
+            },
+        ]
+    );
+    assert!(Properties::load(b"a=1\nb=2")
+        .unwrap()
+        .duplicates()
+        .is_empty());
+}
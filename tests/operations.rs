@@ -5,7 +5,7 @@ fn default() {
     let props = Properties::default();
 
     assert_eq!(props.len(), 0);
-    assert_eq!(props.is_empty(), true);
+    assert!(props.is_empty());
 }
 
 #[test]
@@ -15,7 +15,7 @@ fn insert() {
     props.insert("hello".to_string(), "world".to_string());
 
     assert_eq!(props.len(), 1);
-    assert_eq!(props.is_empty(), false);
+    assert!(!props.is_empty());
 }
 
 #[test]
@@ -92,3 +92,147 @@ fn merge() {
 
     assert_eq!(props.len(), 2);
 }
+
+#[test]
+fn indexed() {
+    let mut props = Properties::default().indexed();
+
+    props.insert_str("hallo", "mutter");
+    props.insert_str("hello", "world");
+    props.insert_str("hallo", "vater");
+
+    assert_eq!(props.get("hallo"), Some("mutter"));
+    assert_eq!(props.get_all("hallo"), vec!["mutter", "vater"]);
+    assert_eq!(&props["hello"], "world");
+    assert_eq!(props.get("missing"), None);
+
+    props.insert_str("hallo", "welt");
+    assert_eq!(props.get_all("hallo"), vec!["mutter", "vater", "welt"]);
+
+    props.delete("hallo");
+    assert_eq!(props.get("hallo"), None);
+    assert_eq!(props.get("hello"), Some("world"));
+}
+
+#[test]
+fn indexed_load() {
+    let props = Properties::load(b"a=1\nb=2\na=3").unwrap().indexed();
+
+    assert_eq!(props.get("a"), Some("1"));
+    assert_eq!(props.get_all("a"), vec!["1", "3"]);
+    assert_eq!(props.get("b"), Some("2"));
+}
+
+#[test]
+fn indexed_iter_mut() {
+    let mut props = Properties::default().indexed();
+
+    props.insert_str("hallo", "welt");
+    assert_eq!(props.get("hallo"), Some("welt"));
+
+    for (k, _) in &mut props {
+        *k = "hello".into();
+    }
+
+    assert_eq!(props.get("hallo"), None);
+    assert_eq!(props.get("hello"), Some("welt"));
+}
+
+fn pairs<'a>(props: &'a Properties) -> Vec<(&'a str, &'a str)> {
+    props.key_values().collect()
+}
+
+#[test]
+fn set() {
+    for props in [
+        Properties::load(b"a=1\nb=2\na=3\nc=4\na=5").unwrap(),
+        Properties::load(b"a=1\nb=2\na=3\nc=4\na=5")
+            .unwrap()
+            .indexed(),
+    ] {
+        let mut props = props;
+
+        assert_eq!(props.set("a", "new").as_deref(), Some("1"));
+        assert_eq!(pairs(&props), vec![("a", "new"), ("b", "2"), ("c", "4")]);
+        assert_eq!(props.line("c"), Some(4));
+
+        assert_eq!(props.set("d".to_string(), "6".to_string()), None);
+        assert_eq!(props.get("d"), Some("6"));
+        assert_eq!(props.line("d"), None);
+    }
+}
+
+#[test]
+fn replace_and_rename() {
+    let mut props = Properties::load(b"a=1\nb=2\na=3").unwrap().indexed();
+
+    assert_eq!(props.replace_all("a", "x"), 2);
+    assert_eq!(props.replace_all("missing", "x"), 0);
+    assert_eq!(pairs(&props), vec![("a", "x"), ("b", "2"), ("a", "x")]);
+
+    assert_eq!(props.rename_key("a", "z"), 2);
+    assert_eq!(props.get("a"), None);
+    assert_eq!(props.get_all("z"), vec!["x", "x"]);
+    assert_eq!(props.line("z"), Some(1));
+}
+
+#[test]
+fn insert_at() {
+    let mut props = Properties::load(b"a=1\nc=3").unwrap().indexed();
+
+    assert!(props.insert_before("c", "b", "2"));
+    assert!(props.insert_after("c", "d", "4"));
+    assert!(!props.insert_after("missing", "e", "5"));
+
+    assert_eq!(
+        pairs(&props),
+        vec![("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")]
+    );
+    assert_eq!(props.line("c"), Some(2));
+    assert_eq!(props.line("d"), None);
+    assert_eq!(props.get("d"), Some("4"));
+}
+
+#[test]
+fn retain() {
+    let mut props = Properties::load(b"a=1\nb=2\nc=3").unwrap().indexed();
+
+    props.retain(|k, v| k != "b" && v != "3");
+
+    assert_eq!(pairs(&props), vec![("a", "1")]);
+    assert_eq!(props.get("c"), None);
+}
+
+#[test]
+fn entry() {
+    use jprops::entry::Entry;
+
+    let mut props = Properties::load(b"count=1\nname=x\nname=y")
+        .unwrap()
+        .indexed();
+
+    props
+        .entry("count")
+        .and_modify(|v| *v = (v.parse::<u32>().unwrap() + 1).to_string().into())
+        .or_insert("0");
+    props
+        .entry("other")
+        .or_insert_with(|| "default".to_string());
+
+    assert_eq!(props.get("count"), Some("2"));
+    assert_eq!(props.get("other"), Some("default"));
+
+    match props.entry("name") {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), "name");
+            assert_eq!(entry.line(), Some(2));
+            assert_eq!(entry.insert("z"), "x");
+            assert_eq!(entry.remove(), "z");
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+
+    assert_eq!(props.get("name"), Some("y"));
+    assert_eq!(props.line("name"), Some(3));
+    assert!(matches!(props.entry("missing"), Entry::Vacant(e) if e.key() == "missing"));
+}
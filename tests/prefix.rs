@@ -0,0 +1,72 @@
+use jprops::Properties;
+use std::borrow::Cow;
+
+const CONTENT: &[u8] = b"db.primary.url=jdbc:primary\n\
+db.primary.user=admin\n\
+db.primaryx=not under db.primary\n\
+db.primary=not under either\n\
+db.replica.url=jdbc:replica\n\
+db.primary.pool.size=10\n\
+app.name=shop\n";
+
+#[test]
+fn prefix() {
+    for props in [
+        Properties::load(CONTENT).unwrap(),
+        Properties::load(CONTENT).unwrap().indexed(),
+    ] {
+        let primary = props.prefix("db.primary");
+
+        assert_eq!(primary.len(), 3);
+        assert_eq!(
+            primary.key_values().collect::<Vec<_>>(),
+            vec![
+                ("url", "jdbc:primary"),
+                ("user", "admin"),
+                ("pool.size", "10")
+            ]
+        );
+        assert_eq!(primary.get("user"), Some("admin"));
+        assert_eq!(primary.get("db.primary.user"), None);
+
+        assert_eq!(
+            props.prefix("db.").children(),
+            vec!["primary", "primaryx", "replica"]
+        );
+        assert_eq!(
+            props.prefix("db").prefix("primary").get("url"),
+            Some("jdbc:primary")
+        );
+        assert_eq!(primary.prefix("pool").get_all("size"), vec!["10"]);
+        assert!(props.prefix("nothing").is_empty());
+        assert_eq!(props.prefix("").len(), props.len());
+    }
+}
+
+#[test]
+fn strip_prefix() {
+    let props = Properties::load(CONTENT).unwrap();
+    let replica = props.strip_prefix("db.replica");
+
+    assert_eq!(
+        replica.key_values().collect::<Vec<_>>(),
+        vec![("url", "jdbc:replica")]
+    );
+    assert_eq!(replica.line("url"), Some(5));
+
+    let mut props = Properties::default();
+    props.insert("owned.key".to_string(), "value".to_string());
+    props.insert_str("owned.borrowed", "value");
+
+    let stripped = props.strip_prefix("owned");
+    let pairs = stripped.into_iter().collect::<Vec<_>>();
+
+    assert_eq!(
+        pairs,
+        vec![
+            (Cow::Borrowed("key"), Cow::Borrowed("value")),
+            (Cow::Borrowed("borrowed"), Cow::Borrowed("value"))
+        ]
+    );
+    assert!(matches!(pairs[1].0, Cow::Borrowed(_)));
+}
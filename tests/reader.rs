@@ -0,0 +1,144 @@
+use jprops::reader::Parser;
+use jprops::{Encoding, Error, EscapeMode, LineEnding, ParseOptions, Properties, Span};
+use std::io::{BufReader, Cursor, Read};
+
+#[test]
+fn from_reader() {
+    let content = b"# comment\nname=value\nother : thing\n";
+
+    let props = Properties::from_reader(Cursor::new(content)).unwrap();
+
+    assert_eq!(props.len(), 2);
+
+    assert_eq!(props.get("name"), Some("value"));
+    assert_eq!(props.get("other"), Some("thing"));
+}
+
+#[test]
+fn small_buffer() {
+    let content = b"targetCities=\\\nDetroit,\\\n\t   Chicago,\\\n  Los Angeles\nlonger.key=some longer value\n";
+
+    let reader = BufReader::with_capacity(3, &content[..]);
+    let props = Properties::from_reader(reader).unwrap();
+
+    assert_eq!(props.len(), 2);
+
+    assert_eq!(
+        props.get("targetCities"),
+        Some("Detroit,Chicago,Los Angeles")
+    );
+    assert_eq!(props.get("longer.key"), Some("some longer value"));
+}
+
+#[test]
+fn line_endings() {
+    let content = b"a=1\r\nb=\\\r\n 2\r\rc=3\r\n";
+
+    // a buffer this small splits every \r\n
+    let mut parser = Parser::new(BufReader::with_capacity(1, &content[..]));
+
+    assert_eq!(
+        parser.by_ref().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+            ("c".to_string(), "3".to_string()),
+        ]
+    );
+    assert_eq!(parser.line(), 5);
+    assert_eq!(parser.line_ending(), Some(LineEnding::Crlf));
+
+    let props = Properties::from_reader(Cursor::new(b"a=1\rb=2")).unwrap();
+
+    assert_eq!(props.line("b"), Some(2));
+    assert_eq!(props.line_ending(), Some(LineEnding::Cr));
+}
+
+#[test]
+fn matches_load() {
+    let content = b"a=1\r\nb\\ c:2\n\nflag\n! comment\nd=\\u0041\\tx\\\n  \\u0042\\";
+
+    let loaded = Properties::load(content).unwrap();
+    let read = Properties::from_reader(Cursor::new(content)).unwrap();
+
+    assert_eq!(
+        loaded.key_values().collect::<Vec<_>>(),
+        read.key_values().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn parser() {
+    let content = b"first=1\nsecond=2\n";
+
+    let mut parser = Parser::new(Cursor::new(content));
+
+    assert_eq!(
+        parser.next().unwrap().unwrap(),
+        ("first".to_string(), "1".to_string())
+    );
+    assert_eq!(parser.line(), 1);
+    assert_eq!(
+        parser.next().unwrap().unwrap(),
+        ("second".to_string(), "2".to_string())
+    );
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn parser_error() {
+    let content = b"first=1\nbad=\\u00G1\nthird=3\n";
+
+    let mut parser = Parser::new(Cursor::new(content));
+
+    assert!(parser.next().unwrap().is_ok());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(Error::InvalidEscape(Span { line: 2, .. }, _)))
+    ));
+    assert!(parser.next().is_none());
+}
+
+struct Failing;
+
+impl Read for Failing {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("broken"))
+    }
+}
+
+#[test]
+fn io_error() {
+    let res = Properties::from_reader(BufReader::new(Failing));
+
+    assert!(matches!(res, Err(Error::Io(_))));
+}
+
+#[test]
+fn parser_options() {
+    let content = b"caf\xe9=\\q\n";
+
+    let opts = ParseOptions {
+        encoding: Encoding::Latin1,
+        ..Default::default()
+    };
+
+    let mut parser = Parser::with_options(Cursor::new(content), opts);
+
+    assert_eq!(
+        parser.next().unwrap().unwrap(),
+        ("caf\u{e9}".to_string(), "q".to_string())
+    );
+
+    let opts = ParseOptions {
+        escapes: EscapeMode::Strict,
+        ..Default::default()
+    };
+
+    let mut parser = Parser::with_options(Cursor::new(&content[4..]), opts);
+
+    assert!(matches!(
+        parser.next(),
+        Some(Err(Error::InvalidEscape(Span { line: 1, .. }, _)))
+    ));
+}
@@ -0,0 +1,138 @@
+use jprops::{EnvLookup, Lookups, Properties, ResolveError};
+use std::collections::HashMap;
+
+#[test]
+fn references() {
+    let content = b"host=localhost\n\
+port=5432\n\
+url=jdbc:postgres://${host}:${port}/${db.name}\n\
+db.name=${app}_db\n\
+app=shop\n\
+price=$5 or $${literal}\n";
+
+    let props = Properties::load(content).unwrap().resolve().unwrap();
+
+    assert_eq!(
+        props.get("url"),
+        Some("jdbc:postgres://localhost:5432/shop_db")
+    );
+    assert_eq!(props.get("price"), Some("$5 or ${literal}"));
+    assert_eq!(props.line("url"), Some(3));
+    assert_eq!(props.len(), 6);
+}
+
+#[test]
+fn defaults() {
+    let content = b"env=prod\n\
+timeout=${timeout.${env}:${timeout.default:30}}\n\
+retries=${retries.${env}:3}\n\
+timeout.prod=60\n\
+empty=${missing:}\n";
+
+    let props = Properties::load(content).unwrap().resolve().unwrap();
+
+    assert_eq!(props.get("timeout"), Some("60"));
+    assert_eq!(props.get("retries"), Some("3"));
+    assert_eq!(props.get("empty"), Some(""));
+}
+
+#[test]
+fn duplicates() {
+    let props = Properties::load(b"a=1\nb=${a}\na=${b}2\n")
+        .unwrap()
+        .resolve()
+        .unwrap();
+
+    assert_eq!(props.get_all("a"), vec!["1", "12"]);
+}
+
+#[test]
+fn cycle() {
+    let props = Properties::load(b"a=${b}\nb=x${c}\nc=${a}\nd=${d}").unwrap();
+
+    assert_eq!(
+        props.resolve().unwrap_err(),
+        ResolveError::Cycle(vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "a".to_string()
+        ])
+    );
+
+    let props = Properties::load(b"d=${d}").unwrap();
+
+    assert_eq!(
+        props.resolve().unwrap_err().to_string(),
+        "reference cycle d -> d"
+    );
+}
+
+#[test]
+fn undefined() {
+    let props = Properties::load(b"a=1\n\nb=${a}${c}").unwrap();
+    let err = props.resolve().unwrap_err();
+
+    assert_eq!(
+        err,
+        ResolveError::Undefined {
+            key: "b".to_string(),
+            line: Some(3),
+            reference: "c".to_string(),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "\"b\" on line 3 references undefined \"c\""
+    );
+
+    let props = Properties::load(b"a=${b").unwrap();
+
+    assert!(matches!(
+        props.resolve(),
+        Err(ResolveError::Unterminated { line: Some(1), .. })
+    ));
+}
+
+#[test]
+fn lookups() {
+    let sys = HashMap::from([("user.name".to_string(), "duke".to_string())]);
+    let env = HashMap::from([("HOME".to_string(), "/home/duke".to_string())]);
+
+    let lookups = Lookups::new().with("env", &env).with("sys", sys);
+
+    let content = b"owner=${sys:user.name}\n\
+data=${env:HOME}/${dir}\n\
+dir=${env:DATA_DIR:data}\n\
+time=${other:12:00}\n";
+
+    let props = Properties::load(content).unwrap();
+    let resolved = props.resolve_with(&lookups).unwrap();
+
+    assert_eq!(resolved.get("owner"), Some("duke"));
+    assert_eq!(resolved.get("data"), Some("/home/duke/data"));
+    assert_eq!(resolved.get("time"), Some("12:00"));
+
+    // the original is left as is
+    assert_eq!(props.get("owner"), Some("${sys:user.name}"));
+
+    let props = Properties::load(b"a=${env:NOPE}").unwrap();
+
+    assert!(matches!(
+        props.resolve_with(&lookups),
+        Err(ResolveError::Undefined { reference, .. }) if reference == "env:NOPE"
+    ));
+}
+
+#[test]
+fn env_lookup() {
+    let path = std::env::var("PATH").unwrap_or_default();
+
+    let lookups = Lookups::new().with("env", EnvLookup);
+    let props = Properties::load(b"path=${env:PATH:}").unwrap();
+
+    assert_eq!(
+        props.resolve_with(&lookups).unwrap().get("path"),
+        Some(path.as_str())
+    );
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "serde")]
+
+use jprops::{from_slice, to_properties, to_string, Error, Properties};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Config {
+    name: String,
+    debug: bool,
+    db: Db,
+    servers: Vec<String>,
+    level: Level,
+    storage: Storage,
+    timeout: Option<u32>,
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Db {
+    url: String,
+    pool: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+enum Storage {
+    Disk { path: String },
+    Memory,
+}
+
+fn config() -> Config {
+    Config {
+        name: "my app".to_string(),
+        debug: false,
+        db: Db {
+            url: "jdbc:postgres://localhost".to_string(),
+            pool: 10,
+        },
+        servers: vec!["alpha".to_string(), "beta".to_string()],
+        level: Level::Info,
+        storage: Storage::Disk {
+            path: "C:\\logs".to_string(),
+        },
+        timeout: None,
+        labels: BTreeMap::from([("team".to_string(), "core".to_string())]),
+    }
+}
+
+#[test]
+fn flattened() {
+    let props = to_properties(&config()).unwrap();
+
+    assert_eq!(
+        props.key_values().collect::<Vec<_>>(),
+        vec![
+            ("name", "my app"),
+            ("debug", "false"),
+            ("db.url", "jdbc:postgres://localhost"),
+            ("db.pool", "10"),
+            ("servers.0", "alpha"),
+            ("servers.1", "beta"),
+            ("level", "info"),
+            ("storage.Disk.path", "C:\\logs"),
+            ("labels.team", "core"),
+        ]
+    );
+}
+
+#[test]
+fn loads_back() {
+    let out = to_string(&config()).unwrap();
+
+    let loaded = Properties::load(out.as_bytes()).unwrap();
+    let props = to_properties(&config()).unwrap();
+
+    assert_eq!(
+        loaded.key_values().collect::<Vec<_>>(),
+        props.key_values().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn round_trip() {
+    let out = to_string(&config()).unwrap();
+
+    assert_eq!(from_slice::<Config>(out.as_bytes()).unwrap(), config());
+}
+
+#[test]
+fn empty_sequences() {
+    let config = Config {
+        servers: vec![],
+        ..config()
+    };
+
+    let props = to_properties(&config).unwrap();
+    assert_eq!(props.get("servers"), Some(""));
+
+    let out = to_string(&config).unwrap();
+    assert_eq!(from_slice::<Config>(out.as_bytes()).unwrap(), config);
+
+    let nested = BTreeMap::from([("lists", vec![vec![], vec![1, 2]])]);
+    let out = to_string(&nested).unwrap();
+
+    assert_eq!(out, "lists.0=\nlists.1.0=1\nlists.1.1=2\n");
+    assert_eq!(
+        from_slice::<BTreeMap<String, Vec<Vec<u8>>>>(out.as_bytes()).unwrap()["lists"],
+        vec![vec![], vec![1, 2]]
+    );
+}
+
+#[test]
+fn top_level_value() {
+    assert!(matches!(to_string(&5), Err(Error::Serde { .. })));
+}
+
+#[test]
+fn bad_map_key() {
+    let map = BTreeMap::from([((1, 2), "x")]);
+
+    assert!(matches!(to_string(&map), Err(Error::Serde { .. })));
+
+    for key in ["k.x", "k[0]", "k]"] {
+        let map = BTreeMap::from([("map", BTreeMap::from([(key, 1)]))]);
+
+        assert!(matches!(to_string(&map), Err(Error::Serde { .. })));
+    }
+
+    let map = BTreeMap::from([("map", BTreeMap::from([('.', 1)]))]);
+    assert!(matches!(to_string(&map), Err(Error::Serde { .. })));
+}
+
+#[test]
+fn empty_some() {
+    let out = to_string(&BTreeMap::from([("opt", Some(String::new()))])).unwrap();
+    assert_eq!(out, "opt=\n");
+
+    let loaded = from_slice::<BTreeMap<String, Option<String>>>(out.as_bytes()).unwrap();
+    assert_eq!(loaded["opt"], None);
+
+    // not optional, so the empty value stays a string
+    let config = Config {
+        name: String::new(),
+        ..config()
+    };
+
+    let out = to_string(&config).unwrap();
+    assert_eq!(from_slice::<Config>(out.as_bytes()).unwrap().name, "");
+}
@@ -0,0 +1,127 @@
+use jprops::{GetError, Properties};
+use std::time::Duration;
+
+const CONTENT: &[u8] = b"port = 8080\n\
+bad.port=eighty\n\
+enabled=Yes\n\
+disabled=off\n\
+timeout=30s\n\
+interval=1h 30m\n\
+iso=PT1H0.5S\n\
+iso.days=P2DT3M\n\
+short=250ms\n\
+cache=10MB\n\
+buffer=512KiB\n\
+hosts=alpha, beta ,gamma\n\
+ports=80;443\n\
+empty=\n";
+
+fn props() -> Properties<'static> {
+    Properties::load(CONTENT).unwrap()
+}
+
+#[test]
+fn get_as() {
+    let props = props();
+
+    assert_eq!(props.get_as::<u16>("port"), Ok(Some(8080)));
+    assert_eq!(props.get_as::<u16>("missing"), Ok(None));
+
+    let err = props.get_as::<u16>("bad.port").unwrap_err();
+
+    assert_eq!(
+        err,
+        GetError {
+            key: "bad.port".to_string(),
+            value: "eighty".to_string(),
+            line: Some(2),
+            message: "invalid digit found in string".to_string(),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "\"bad.port\" on line 2 has invalid value \"eighty\": invalid digit found in string"
+    );
+}
+
+#[test]
+fn get_bool() {
+    let props = props();
+
+    assert_eq!(props.get_bool("enabled"), Ok(Some(true)));
+    assert_eq!(props.get_bool("disabled"), Ok(Some(false)));
+    assert!(props.get_bool("port").is_err());
+}
+
+#[test]
+fn get_duration() {
+    let props = props();
+
+    assert_eq!(
+        props.get_duration("timeout"),
+        Ok(Some(Duration::from_secs(30)))
+    );
+    assert_eq!(
+        props.get_duration("interval"),
+        Ok(Some(Duration::from_secs(5400)))
+    );
+    assert_eq!(
+        props.get_duration("iso"),
+        Ok(Some(Duration::from_millis(3_600_500)))
+    );
+    assert_eq!(
+        props.get_duration("iso.days"),
+        Ok(Some(Duration::from_secs(2 * 86400 + 180)))
+    );
+    assert_eq!(
+        props.get_duration("short"),
+        Ok(Some(Duration::from_millis(250)))
+    );
+
+    let err = props.get_duration("port").unwrap_err();
+    assert_eq!(err.message, "missing a unit, like s or ms");
+    assert_eq!(err.line, Some(1));
+
+    let mut props = Properties::default();
+    props.insert_str("order", "PT1S1H");
+    props.insert_str("unit", "3 weeks");
+
+    assert!(props.get_duration("order").is_err());
+    assert_eq!(
+        props.get_duration("unit").unwrap_err().message,
+        "unknown unit \"weeks\""
+    );
+}
+
+#[test]
+fn get_size() {
+    let props = props();
+
+    assert_eq!(props.get_size("cache"), Ok(Some(10_000_000)));
+    assert_eq!(props.get_size("buffer"), Ok(Some(512 * 1024)));
+    assert_eq!(props.get_size("port"), Ok(Some(8080)));
+    assert!(props.get_size("timeout").is_err());
+}
+
+#[test]
+fn get_list() {
+    let props = props();
+
+    assert_eq!(
+        props.get_list::<String>("hosts", ','),
+        Ok(Some(vec![
+            "alpha".to_string(),
+            "beta".to_string(),
+            "gamma".to_string()
+        ]))
+    );
+    assert_eq!(props.get_list::<u16>("ports", ';'), Ok(Some(vec![80, 443])));
+    assert_eq!(props.get_list::<u16>("empty", ','), Ok(Some(vec![])));
+
+    let err = props.get_list::<u16>("hosts", ',').unwrap_err();
+    assert_eq!(err.line, Some(12));
+    assert_eq!(
+        err.message,
+        "item \"alpha\" is invalid, invalid digit found in string"
+    );
+}
@@ -0,0 +1,179 @@
+use jprops::{LineEnding, Properties, Separator, WriteOptions};
+
+fn props() -> Properties<'static> {
+    let mut props = Properties::default();
+
+    props.insert_str("hello", "world");
+    props.insert_str("hallo", "welt");
+    props.insert_str("name", "\u{e9}t\u{e9} \u{2603} \u{1f600}");
+
+    props
+}
+
+fn write(props: &Properties, opts: &WriteOptions) -> String {
+    let mut out = Vec::new();
+    props.write_to(&mut out, opts).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn defaults() {
+    let props = props();
+
+    let latin1 = WriteOptions {
+        latin1_only: true,
+        ..Default::default()
+    };
+
+    assert_eq!(write(&props, &latin1), props.to_string());
+}
+
+#[test]
+fn utf8() {
+    let props = props();
+
+    let out = write(&props, &WriteOptions::default());
+
+    assert!(out.ends_with("name=\u{e9}t\u{e9} \u{2603} \u{1f600}\n"));
+}
+
+#[test]
+fn latin1_only() {
+    let props = props();
+
+    let opts = WriteOptions {
+        latin1_only: true,
+        ..Default::default()
+    };
+
+    let out = write(&props, &opts);
+
+    assert!(out.ends_with("name=\u{e9}t\u{e9} \\u2603 \\uD83D\\uDE00\n"));
+}
+
+#[test]
+fn separators() {
+    let props = props();
+
+    let colon = WriteOptions {
+        separator: Separator::Colon,
+        ..Default::default()
+    };
+
+    let spaced = WriteOptions {
+        separator: Separator::SpacedEquals,
+        ..Default::default()
+    };
+
+    assert!(write(&props, &colon).starts_with("hello:world\nhallo:welt\n"));
+    assert!(write(&props, &spaced).starts_with("hello = world\nhallo = welt\n"));
+}
+
+#[test]
+fn crlf() {
+    let props = props();
+
+    let opts = WriteOptions {
+        line_ending: LineEnding::Crlf,
+        header: Some("first\nsecond".to_string()),
+        ..Default::default()
+    };
+
+    assert!(write(&props, &opts).starts_with("#first\r\n#second\r\nhello=world\r\nhallo=welt\r\n"));
+
+    let opts = WriteOptions {
+        line_ending: LineEnding::Cr,
+        ..Default::default()
+    };
+
+    assert!(write(&props, &opts).starts_with("hello=world\rhallo=welt\r"));
+}
+
+#[test]
+fn ascii_only() {
+    let props = props();
+
+    let opts = WriteOptions {
+        ascii_only: true,
+        ..Default::default()
+    };
+
+    let out = write(&props, &opts);
+
+    assert!(out.is_ascii());
+    assert!(out.ends_with("name=\\u00E9t\\u00E9 \\u2603 \\uD83D\\uDE00\n"));
+}
+
+#[test]
+fn header() {
+    let props = props();
+
+    let opts = WriteOptions {
+        header: Some("generated\n! keep\n#as is".to_string()),
+        ..Default::default()
+    };
+
+    assert!(write(&props, &opts).starts_with("#generated\n! keep\n#as is\nhello=world\n"));
+
+    let opts = WriteOptions {
+        header: Some("a\rinjected=yes\r\n!b\n".to_string()),
+        ..Default::default()
+    };
+
+    let out = write(&props, &opts);
+    assert!(out.starts_with("#a\n#injected=yes\n!b\nhello=world\n"));
+
+    let loaded = Properties::load(out.as_bytes()).unwrap();
+    assert_eq!(loaded.get("injected"), None);
+}
+
+#[test]
+fn timestamp() {
+    let props = props();
+
+    let opts = WriteOptions {
+        timestamp: true,
+        ..Default::default()
+    };
+
+    let out = write(&props, &opts);
+    let (date, rest) = out.split_once('\n').unwrap();
+
+    assert!(date.starts_with('#'));
+    assert!(date.contains(" UTC "));
+    assert!(rest.starts_with("hello=world\n"));
+}
+
+#[test]
+fn sorted() {
+    let mut props = props();
+    props.insert_str("hallo", "mutter");
+
+    let opts = WriteOptions {
+        sorted: true,
+        ..Default::default()
+    };
+
+    assert!(write(&props, &opts).starts_with("hallo=welt\nhallo=mutter\nhello=world\nname="));
+}
+
+#[test]
+fn round_trip() {
+    let props = props();
+
+    let opts = WriteOptions {
+        separator: Separator::SpacedEquals,
+        line_ending: LineEnding::Crlf,
+        header: Some("header".to_string()),
+        timestamp: true,
+        ..Default::default()
+    };
+
+    let out = write(&props, &opts);
+    let loaded = Properties::load(out.as_bytes()).unwrap();
+
+    assert_eq!(
+        loaded.key_values().collect::<Vec<_>>(),
+        props.key_values().collect::<Vec<_>>()
+    );
+}